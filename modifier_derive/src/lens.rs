@@ -0,0 +1,70 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{Attribute, DeriveInput, Ident, LitStr};
+
+use util;
+
+struct Field {
+    name: Option<Ident>,
+    skip: bool,
+}
+
+fn field_attrs(attrs: &[Attribute]) -> syn::Result<Field> {
+    let mut field = Field { name: None, skip: false };
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("lens")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                field.name = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                field.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported lens field attribute"))
+            }
+        })?;
+    }
+
+    Ok(field)
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = util::struct_fields(&input, "Lens")?;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut lenses = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = field_attrs(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+
+        let field_name = util::field_name(field, index);
+        let name = attrs.name.unwrap_or_else(|| {
+            let name = match field.ident {
+                Some(_) => field_name.to_uppercase(),
+                None => format!("FIELD_{}", index),
+            };
+            Ident::new(&name, Span::call_site())
+        });
+        let vis = &field.vis;
+        let member = util::member(field, index);
+        let ty = &field.ty;
+        let doc = format!("A lens viewing the `{}` field of `{}`.", field_name, ident);
+
+        lenses.push(quote! {
+            #[doc = #doc]
+            #vis const #name: ::modifier::FieldLens<Self, #ty> = ::modifier::FieldLens::new(
+                |source| &source.#member,
+                |source| &mut source.#member,
+            );
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#lenses)*
+        }
+    })
+}
@@ -0,0 +1,129 @@
+#![deny(missing_docs, warnings)]
+
+//! Derive macros for the `modifier` crate.
+//!
+//! `#[derive(Set)]` implements `modifier::Set` for a struct and generates
+//! one `Modifier` type per field, so a struct gets the fluent `set` and
+//! `set_mut` API without hand-written boilerplate. `#[derive(Patch)]`
+//! generates a partial-update type for a struct, and `#[derive(Diff)]`
+//! computes such a patch from two values. `#[derive(Lens)]` generates a
+//! lens for every field of a struct.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate syn;
+#[macro_use]
+extern crate quote;
+
+use proc_macro::TokenStream;
+use syn::DeriveInput;
+
+mod lens;
+mod patch;
+mod set;
+mod util;
+
+/// Implements `Set` for a struct and generates a modifier per field.
+///
+/// By default the modifier for a field `foo_bar` is called `ModifyFooBar`
+/// and the modifier for the field at position `0` of a tuple struct is
+/// called `Modify0`. Each modifier is a tuple struct wrapping the new value
+/// and shares the visibility of the deriving struct. Every generated
+/// modifier also implements `ReversibleModifier`, with an inverse holding
+/// the previous value of the field.
+///
+/// The derive also generates an enum called `FooModifier` for a struct
+/// `Foo`, with one variant per modifier holding the new value of the field,
+/// such as `FooModifier::FooBar` or `FooModifier::Field0`. The enum is a
+/// `ReversibleModifier` of `Foo`, so a `Vec<FooModifier>` is a list of
+/// changes to `Foo` assembled at runtime.
+///
+/// Modifier names do not include the name of the struct, so two structs in
+/// the same module which derive `Set` and share a field name both generate
+/// the same modifier and fail to compile. Give one of them a distinct
+/// `#[modifier(prefix = "...")]`, or rename the field's modifier.
+///
+/// Container attributes:
+///
+/// - `#[modifier(prefix = "...")]` replaces the `Modify` prefix.
+/// - `#[modifier(into)]` makes every modifier accept any `V: Into<Field>`.
+/// - `#[modifier(enum_name = "...")]` names the modifier enum explicitly.
+/// - `#[modifier(derive(...))]` adds the listed derives to every modifier
+///   and to the enum. When these include serde's `Serialize` or
+///   `Deserialize`, a modifier is serialized as a map from the field name to
+///   the new value, so `ModifyFooBar(1)` and `FooModifier::FooBar(1)` both
+///   become `{"foo_bar":1}`.
+///
+/// Field attributes:
+///
+/// - `#[modifier(name = "...")]` names the modifier explicitly.
+/// - `#[modifier(into)]` makes the modifier accept any `V: Into<Field>`.
+/// - `#[modifier(skip)]` generates no modifier for the field.
+#[proc_macro_derive(Set, attributes(modifier))]
+pub fn derive_set(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    set::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a patch type for a struct and implements `Patchable`.
+///
+/// The patch of `Foo` is called `FooPatch` and has the same shape as `Foo`,
+/// with every field wrapped in an `Option`. As a modifier of `Foo` it
+/// overwrites only the fields which are `Some`, and patches can be combined
+/// with `Merge::merge`, where the later patch wins.
+///
+/// Container attributes:
+///
+/// - `#[patch(name = "...")]` names the patch type explicitly.
+/// - `#[patch(derive(...))]` adds the listed derives to the patch type. When
+///   these include serde's `Serialize` or `Deserialize`, absent fields are
+///   omitted when serializing and default to `None` when deserializing.
+///
+/// Field attributes:
+///
+/// - `#[patch(nested)]` patches a `Patchable` field with its own patch type
+///   instead of replacing it, and merges nested patches field by field.
+/// - `#[patch(skip)]` leaves the field out of the patch. Generic parameters
+///   which only skipped fields use are kept in a hidden `PhantomData` field
+///   of the patch, called `_marker` or placed last in a tuple struct.
+#[proc_macro_derive(Patch, attributes(patch))]
+pub fn derive_patch(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    patch::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `Diff` for a struct which also derives `Patch`.
+///
+/// Fields are compared with `PartialEq`; changed fields are cloned into
+/// the patch, and changed `#[patch(nested)]` fields are diffed recursively.
+/// The `#[patch(...)]` attributes are shared with `#[derive(Patch)]`.
+#[proc_macro_derive(Diff, attributes(patch))]
+pub fn derive_diff(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    patch::expand_diff(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates an associated `FieldLens` constant for every field of a struct.
+///
+/// The lens for a field `foo_bar` is called `FOO_BAR`, and the lens for
+/// the field at position `0` of a tuple struct is called `FIELD_0`. Each
+/// lens shares the visibility of its field.
+///
+/// Field attributes:
+///
+/// - `#[lens(name = "...")]` names the lens explicitly.
+/// - `#[lens(skip)]` generates no lens for the field.
+#[proc_macro_derive(Lens, attributes(lens))]
+pub fn derive_lens(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    lens::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
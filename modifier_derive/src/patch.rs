@@ -0,0 +1,275 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{Attribute, DeriveInput, Fields, Ident, Index, LitStr, Member, Path};
+
+use util;
+
+struct Container {
+    name: Option<Ident>,
+    derives: Vec<Path>,
+}
+
+struct Field {
+    nested: bool,
+    skip: bool,
+}
+
+fn container_attrs(attrs: &[Attribute]) -> syn::Result<Container> {
+    let mut container = Container { name: None, derives: Vec::new() };
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("patch")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                container.name = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                util::parse_derives(&meta, &mut container.derives)
+            } else {
+                Err(meta.error("unsupported patch container attribute"))
+            }
+        })?;
+    }
+
+    Ok(container)
+}
+
+fn field_attrs(attrs: &[Attribute]) -> syn::Result<Field> {
+    let mut field = Field { nested: false, skip: false };
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("patch")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("nested") {
+                field.nested = true;
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                field.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported patch field attribute"))
+            }
+        })?;
+    }
+
+    Ok(field)
+}
+
+fn patch_name(input: &DeriveInput, container: &Container) -> Ident {
+    container.name.clone().unwrap_or_else(|| {
+        Ident::new(&format!("{}Patch", input.ident), Span::call_site())
+    })
+}
+
+/// A hidden field of the patch which uses the generic parameters that only
+/// skipped fields mention, since the patch would otherwise leave them unused.
+struct Marker {
+    member: Member,
+    ty: TokenStream,
+}
+
+fn marker(input: &DeriveInput, fields: &Fields) -> syn::Result<Option<Marker>> {
+    let mut patched = Vec::new();
+    for field in fields.iter() {
+        if !field_attrs(&field.attrs)?.skip {
+            patched.push(&field.ty);
+        }
+    }
+
+    let ty = match util::unused_params(&input.generics, patched.iter().cloned()) {
+        Some(ty) => ty,
+        None => return Ok(None),
+    };
+    let member = match *fields {
+        Fields::Named(_) => Member::Named(Ident::new("_marker", Span::call_site())),
+        _ => Member::Unnamed(Index::from(patched.len())),
+    };
+    Ok(Some(Marker { member, ty }))
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = util::struct_fields(&input, "Patch")?;
+    let container = container_attrs(&input.attrs)?;
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let derives = &container.derives;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+    let patch = patch_name(&input, &container);
+    let serde = util::find_derive(derives, "Serialize").is_some()
+        || util::find_derive(derives, "Deserialize").is_some();
+    let serde_attr = if serde {
+        quote!(#[serde(default, skip_serializing_if = "::std::option::Option::is_none")])
+    } else {
+        quote!()
+    };
+
+    let mut definitions = Vec::new();
+    let mut members = Vec::new();
+    let mut merges = Vec::new();
+    let mut applies = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = field_attrs(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+
+        let field_vis = &field.vis;
+        let field_ident = &field.ident;
+        let colon = field.colon_token;
+        let member = util::member(field, index);
+        let patch_member = util::member(field, members.len());
+        let ty = &field.ty;
+        let doc = format!("The new value of `{}`, if any.", util::field_name(field, index));
+
+        if attrs.nested {
+            definitions.push(quote! {
+                #[doc = #doc]
+                #serde_attr
+                #field_vis #field_ident #colon ::std::option::Option<<#ty as ::modifier::Patchable>::Patch>
+            });
+            merges.push(quote! {
+                #patch_member: ::modifier::Merge::merge(self.#patch_member, other.#patch_member)
+            });
+            applies.push(quote! {
+                ::modifier::Modifier::modify(self.#patch_member, &mut target.#member);
+            });
+        } else {
+            definitions.push(quote! {
+                #[doc = #doc]
+                #serde_attr
+                #field_vis #field_ident #colon ::std::option::Option<#ty>
+            });
+            merges.push(quote! {
+                #patch_member: other.#patch_member.or(self.#patch_member)
+            });
+            applies.push(quote! {
+                if let ::std::option::Option::Some(value) = self.#patch_member {
+                    target.#member = value;
+                }
+            });
+        }
+        members.push(patch_member);
+    }
+
+    let mut defaults: Vec<_> = members.iter()
+        .map(|member| quote!(#member: ::std::option::Option::None))
+        .collect();
+    if let Some(marker) = marker(&input, fields)? {
+        let member = &marker.member;
+        let ty = &marker.ty;
+        let serde_skip = if serde { quote!(#[serde(skip)]) } else { quote!() };
+        definitions.push(match *fields {
+            Fields::Named(_) => quote!(#[doc(hidden)] #serde_skip #vis #member: #ty),
+            _ => quote!(#[doc(hidden)] #serde_skip #vis #ty),
+        });
+        defaults.push(quote!(#member: ::std::marker::PhantomData));
+        merges.push(quote!(#member: ::std::marker::PhantomData));
+    }
+
+    let doc = format!("A partial update of `{}`.", ident);
+    let definition = match *fields {
+        Fields::Named(_) => quote! {
+            #vis struct #patch #generics #where_clause {
+                #(#definitions,)*
+            }
+        },
+        Fields::Unnamed(_) => quote! {
+            #vis struct #patch #generics (#(#definitions,)*) #where_clause;
+        },
+        Fields::Unit => quote! {
+            #vis struct #patch #generics #where_clause;
+        },
+    };
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(#(#derives),*)]
+        #definition
+
+        impl #impl_generics ::std::default::Default for #patch #ty_generics #where_clause {
+            fn default() -> Self {
+                #patch { #(#defaults,)* }
+            }
+        }
+
+        impl #impl_generics ::modifier::Merge for #patch #ty_generics #where_clause {
+            fn merge(self, other: Self) -> Self {
+                #patch { #(#merges,)* }
+            }
+        }
+
+        impl #impl_generics ::modifier::Modifier<#ident #ty_generics> for #patch #ty_generics
+        #where_clause {
+            fn modify(self, target: &mut #ident #ty_generics) {
+                #(#applies)*
+            }
+        }
+
+        impl #impl_generics ::modifier::Patchable for #ident #ty_generics #where_clause {
+            type Patch = #patch #ty_generics;
+        }
+    })
+}
+
+pub fn expand_diff(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = util::struct_fields(&input, "Diff")?;
+    let container = container_attrs(&input.attrs)?;
+    let patch = patch_name(&input, &container);
+    let ident = &input.ident;
+
+    let mut generics = input.generics.clone();
+    let generic = input.generics.params.iter().next().is_some();
+    let mut diffs = Vec::new();
+    let mut position = 0;
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = field_attrs(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+
+        let member = util::member(field, index);
+        let patch_member = util::member(field, position);
+        position += 1;
+        let ty = &field.ty;
+        if attrs.nested {
+            if generic {
+                generics.make_where_clause().predicates.push(parse_quote! {
+                    #ty: ::modifier::Diff + ::std::cmp::PartialEq
+                });
+            }
+            diffs.push(quote! {
+                #patch_member: if self.#member != new.#member {
+                    ::std::option::Option::Some(::modifier::Diff::diff(&self.#member, &new.#member))
+                } else {
+                    ::std::option::Option::None
+                }
+            });
+        } else {
+            if generic {
+                generics.make_where_clause().predicates.push(parse_quote! {
+                    #ty: ::std::clone::Clone + ::std::cmp::PartialEq
+                });
+            }
+            diffs.push(quote! {
+                #patch_member: if self.#member != new.#member {
+                    ::std::option::Option::Some(::std::clone::Clone::clone(&new.#member))
+                } else {
+                    ::std::option::Option::None
+                }
+            });
+        }
+    }
+
+    if let Some(marker) = marker(&input, fields)? {
+        let member = &marker.member;
+        diffs.push(quote!(#member: ::std::marker::PhantomData));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::modifier::Diff for #ident #ty_generics #where_clause {
+            fn diff(&self, new: &Self) -> #patch #ty_generics {
+                #patch { #(#diffs,)* }
+            }
+        }
+    })
+}
@@ -0,0 +1,343 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{Attribute, DeriveInput, Ident, LitStr, Path, Type};
+
+use util;
+
+struct Container {
+    prefix: String,
+    into: bool,
+    enum_name: Option<Ident>,
+    derives: Vec<Path>,
+}
+
+struct Field {
+    name: Option<Ident>,
+    into: bool,
+    skip: bool,
+}
+
+fn container_attrs(attrs: &[Attribute]) -> syn::Result<Container> {
+    let mut container = Container {
+        prefix: "Modify".to_owned(),
+        into: false,
+        enum_name: None,
+        derives: Vec::new(),
+    };
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("modifier")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("prefix") {
+                container.prefix = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("into") {
+                container.into = true;
+                Ok(())
+            } else if meta.path.is_ident("enum_name") {
+                container.enum_name = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                util::parse_derives(&meta, &mut container.derives)
+            } else {
+                Err(meta.error("unsupported modifier container attribute"))
+            }
+        })?;
+    }
+
+    Ok(container)
+}
+
+fn field_attrs(attrs: &[Attribute]) -> syn::Result<Field> {
+    let mut field = Field { name: None, into: false, skip: false };
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("modifier")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                field.name = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("into") {
+                field.into = true;
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                field.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported modifier field attribute"))
+            }
+        })?;
+    }
+
+    Ok(field)
+}
+
+/// Serde implementations for a field modifier which name the field, so that
+/// `ModifyName("b")` is serialized as `{"name":"b"}` instead of `"b"`.
+///
+/// The modifier is generic over its value when `value` is given, and wraps a
+/// `ty` otherwise.
+fn tagged(name: &Ident,
+          key: &str,
+          value: Option<&Ident>,
+          ty: &Type,
+          serialize: Option<&Path>,
+          deserialize: Option<&Path>) -> TokenStream {
+    let mut impls = Vec::new();
+
+    if let Some(serialize) = serialize {
+        let imp = match value {
+            Some(value) => quote! {
+                impl<#value> _serde::Serialize for #name<#value> where #value: _serde::Serialize
+            },
+            None => quote!(impl _serde::Serialize for #name),
+        };
+        impls.push(quote! {
+            #[derive(#serialize)]
+            struct Tagged<'a, T: 'a> {
+                #[serde(rename = #key)]
+                value: &'a T,
+            }
+
+            #imp {
+                fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                    where S: _serde::Serializer {
+                    _serde::Serialize::serialize(&Tagged { value: &self.0 }, serializer)
+                }
+            }
+        });
+    }
+
+    if let Some(deserialize) = deserialize {
+        let (imp, inner) = match value {
+            Some(value) => (quote! {
+                impl<'de, #value> _serde::Deserialize<'de> for #name<#value>
+                    where #value: _serde::Deserialize<'de>
+            }, quote!(#value)),
+            None => (quote!(impl<'de> _serde::Deserialize<'de> for #name), quote!(#ty)),
+        };
+        impls.push(quote! {
+            #[derive(#deserialize)]
+            #[serde(deny_unknown_fields)]
+            struct TaggedOwned<T> {
+                #[serde(rename = #key)]
+                value: T,
+            }
+
+            #imp {
+                fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+                    where D: _serde::Deserializer<'de> {
+                    let tagged: TaggedOwned<#inner> = _serde::Deserialize::deserialize(deserializer)?;
+                    ::std::result::Result::Ok(#name(tagged.value))
+                }
+            }
+        });
+    }
+
+    if impls.is_empty() {
+        return quote!();
+    }
+    quote! {
+        const _: () = {
+            extern crate serde as _serde;
+
+            #(#impls)*
+        };
+    }
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = util::struct_fields(&input, "Set")?;
+    let container = container_attrs(&input.attrs)?;
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let generics = &input.generics;
+    let serialize = util::find_derive(&container.derives, "Serialize");
+    let deserialize = util::find_derive(&container.derives, "Deserialize");
+    let serde = serialize.is_some() || deserialize.is_some();
+    let derives = &container.derives;
+    let modifier_derives: Vec<_> = derives.iter()
+        .filter(|path| {
+            !path.segments.last().is_some_and(|segment| {
+                segment.ident == "Serialize" || segment.ident == "Deserialize"
+            })
+        })
+        .collect();
+    let generic = input.generics.params.iter().next().is_some();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let value = util::fresh_param(&input.generics, "V");
+    let enum_name = container.enum_name.clone().unwrap_or_else(|| {
+        Ident::new(&format!("{}Modifier", ident), Span::call_site())
+    });
+
+    let mut modifiers = Vec::new();
+    let mut variants = Vec::new();
+    let mut applies = Vec::new();
+    let mut reverses = Vec::new();
+    let mut types = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = field_attrs(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+
+        let field_name = util::field_name(field, index);
+        let name = attrs.name.unwrap_or_else(|| {
+            let name = format!("{}{}", container.prefix, util::camel_case(&field_name));
+            Ident::new(&name, Span::call_site())
+        });
+        let member = util::member(field, index);
+        let ty = &field.ty;
+        let doc = format!("Sets the `{}` field of `{}`.", field_name, ident);
+
+        let variant = match field.ident {
+            Some(_) => util::camel_case(&field_name),
+            None => format!("Field{}", field_name),
+        };
+        let variant = Ident::new(&variant, Span::call_site());
+        let variant_doc = format!("Sets the `{}` field.", field_name);
+        let rename = if serde { quote!(#[serde(rename = #field_name)]) } else { quote!() };
+        variants.push(quote! {
+            #[doc = #variant_doc]
+            #rename
+            #variant(#ty)
+        });
+        applies.push(quote! {
+            #enum_name::#variant(value) => target.#member = value,
+        });
+        reverses.push(quote! {
+            #enum_name::#variant(value) => {
+                #enum_name::#variant(::std::mem::replace(&mut target.#member, value))
+            }
+        });
+        types.push(ty);
+
+        let modifier = if attrs.into || container.into {
+            let mut generics = input.generics.clone();
+            generics.params.push(parse_quote!(#value));
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#value: ::std::convert::Into<#ty>));
+            let (impl_generics, _, where_clause) = generics.split_for_impl();
+            let tagged = tagged(&name, &field_name, Some(&value), ty, serialize, deserialize);
+            quote! {
+                #[doc = #doc]
+                #[derive(#(#modifier_derives),*)]
+                #vis struct #name<#value>(pub #value);
+
+                #tagged
+
+                impl #impl_generics ::modifier::Modifier<#ident #ty_generics> for #name<#value>
+                #where_clause {
+                    fn modify(self, target: &mut #ident #ty_generics) {
+                        target.#member = self.0.into();
+                    }
+                }
+
+                impl #impl_generics ::modifier::ReversibleModifier<#ident #ty_generics> for #name<#value>
+                #where_clause {
+                    type Inverse = #name<#ty>;
+
+                    fn modify_reversible(self, target: &mut #ident #ty_generics) -> #name<#ty> {
+                        #name(::std::mem::replace(&mut target.#member, self.0.into()))
+                    }
+                }
+            }
+        } else if generic {
+            let tagged = tagged(&name, &field_name, Some(&value), ty, serialize, deserialize);
+            quote! {
+                #[doc = #doc]
+                #[derive(#(#modifier_derives),*)]
+                #vis struct #name<#value>(pub #value);
+
+                #tagged
+
+                impl #impl_generics ::modifier::Modifier<#ident #ty_generics> for #name<#ty>
+                #where_clause {
+                    fn modify(self, target: &mut #ident #ty_generics) {
+                        target.#member = self.0;
+                    }
+                }
+
+                impl #impl_generics ::modifier::ReversibleModifier<#ident #ty_generics> for #name<#ty>
+                #where_clause {
+                    type Inverse = Self;
+
+                    fn modify_reversible(self, target: &mut #ident #ty_generics) -> Self {
+                        #name(::std::mem::replace(&mut target.#member, self.0))
+                    }
+                }
+            }
+        } else {
+            let tagged = tagged(&name, &field_name, None, ty, serialize, deserialize);
+            quote! {
+                #[doc = #doc]
+                #[derive(#(#modifier_derives),*)]
+                #vis struct #name(pub #ty);
+
+                #tagged
+
+                impl ::modifier::Modifier<#ident> for #name {
+                    fn modify(self, target: &mut #ident) {
+                        target.#member = self.0;
+                    }
+                }
+
+                impl ::modifier::ReversibleModifier<#ident> for #name {
+                    type Inverse = Self;
+
+                    fn modify_reversible(self, target: &mut #ident) -> Self {
+                        #name(::std::mem::replace(&mut target.#member, self.0))
+                    }
+                }
+            }
+        };
+        modifiers.push(modifier);
+    }
+
+    // Generic parameters which only skipped fields use are kept in an
+    // uninhabited hidden variant, since the enum would otherwise leave them
+    // unused.
+    if let Some(marker) = util::unused_params(generics, types) {
+        let skip = if serde { quote!(#[serde(skip)]) } else { quote!() };
+        variants.push(quote! {
+            #[doc(hidden)]
+            #skip
+            __Marker(#marker, ::std::convert::Infallible)
+        });
+        applies.push(quote!(#enum_name::__Marker(_, never) => match never {},));
+        reverses.push(quote!(#enum_name::__Marker(_, never) => match never {},));
+    }
+
+    let enum_doc = format!("A modifier which sets any one field of `{}`.", ident);
+    Ok(quote! {
+        impl #impl_generics ::modifier::Set for #ident #ty_generics #where_clause {}
+
+        #(#modifiers)*
+
+        #[doc = #enum_doc]
+        #[derive(#(#derives),*)]
+        #vis enum #enum_name #generics #where_clause {
+            #(#variants,)*
+        }
+
+        impl #impl_generics ::modifier::Modifier<#ident #ty_generics> for #enum_name #ty_generics
+        #where_clause {
+            fn modify(self, target: &mut #ident #ty_generics) {
+                match self {
+                    #(#applies)*
+                }
+            }
+        }
+
+        impl #impl_generics ::modifier::ReversibleModifier<#ident #ty_generics>
+        for #enum_name #ty_generics #where_clause {
+            type Inverse = Self;
+
+            fn modify_reversible(self, target: &mut #ident #ty_generics) -> Self {
+                match self {
+                    #(#reverses)*
+                }
+            }
+        }
+    })
+}
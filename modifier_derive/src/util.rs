@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::meta::ParseNestedMeta;
+use syn::{Data, DeriveInput, Fields, Generics, Ident, Index, Member, Path, Type};
+
+/// The fields of a struct, or an error for enums and unions.
+pub fn struct_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a Fields> {
+    match input.data {
+        Data::Struct(ref data) => Ok(&data.fields),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("#[derive({})] is only supported on structs", derive),
+        )),
+    }
+}
+
+/// The expression member used to access the field at `index`.
+pub fn member(field: &syn::Field, index: usize) -> Member {
+    match field.ident {
+        Some(ref ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+    }
+}
+
+/// The name of the field at `index`, without any raw identifier prefix.
+pub fn field_name(field: &syn::Field, index: usize) -> String {
+    match field.ident {
+        Some(ref ident) => {
+            let name = ident.to_string();
+            name.trim_start_matches("r#").to_owned()
+        }
+        None => index.to_string(),
+    }
+}
+
+/// Converts a `snake_case` field name to `CamelCase`.
+pub fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = true;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// An identifier starting with `base` that is not a type parameter of `generics`.
+pub fn fresh_param(generics: &Generics, base: &str) -> Ident {
+    let mut name = base.to_owned();
+    while generics.type_params().any(|param| param.ident == name) {
+        name.push('_');
+    }
+    Ident::new(&name, Span::call_site())
+}
+
+/// Parses the paths listed in a `derive(...)` attribute argument.
+pub fn parse_derives(meta: &ParseNestedMeta, derives: &mut Vec<Path>) -> syn::Result<()> {
+    meta.parse_nested_meta(|derive| {
+        derives.push(derive.path);
+        Ok(())
+    })
+}
+
+/// The derive in `derives` whose last path segment is `name`, if any.
+pub fn find_derive<'a>(derives: &'a [Path], name: &str) -> Option<&'a Path> {
+    derives.iter().find(|path| {
+        path.segments.last().is_some_and(|segment| segment.ident == name)
+    })
+}
+
+/// A `PhantomData` type using the generic parameters of `generics` which
+/// none of `types` mention, or `None` if every parameter is used.
+pub fn unused_params<'a, I>(generics: &Generics, types: I) -> Option<TokenStream>
+    where I: IntoIterator<Item = &'a Type> {
+    let mut used = HashSet::new();
+    for ty in types {
+        collect_names(ty.to_token_stream(), &mut used);
+    }
+
+    let types: Vec<_> = generics.type_params()
+        .map(|param| &param.ident)
+        .filter(|ident| !used.contains(&ident.to_string()))
+        .collect();
+    let lifetimes: Vec<_> = generics.lifetimes()
+        .map(|param| &param.lifetime)
+        .filter(|lifetime| !used.contains(&lifetime.to_string()))
+        .collect();
+    if types.is_empty() && lifetimes.is_empty() {
+        return None;
+    }
+
+    Some(quote! {
+        ::std::marker::PhantomData<(#(fn() -> *const #types,)* #(&#lifetimes (),)*)>
+    })
+}
+
+/// Collects every identifier and lifetime mentioned in `tokens`.
+fn collect_names(tokens: TokenStream, names: &mut HashSet<String>) {
+    let mut lifetime = false;
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => collect_names(group.stream(), names),
+            TokenTree::Punct(ref punct) if punct.as_char() == '\'' => {
+                lifetime = true;
+                continue;
+            }
+            TokenTree::Ident(ident) if lifetime => {
+                names.insert(format!("'{}", ident));
+            }
+            TokenTree::Ident(ident) => {
+                names.insert(ident.to_string());
+            }
+            TokenTree::Punct(_) | TokenTree::Literal(_) => (),
+        }
+        lifetime = false;
+    }
+}
@@ -0,0 +1,42 @@
+extern crate modifier;
+
+use modifier::{Lens, Set};
+
+#[derive(Set, Lens)]
+pub struct Engine {
+    pub power: u32,
+}
+
+#[derive(Set, Lens)]
+pub struct Car {
+    pub engine: Engine,
+    #[lens(name = "NAME")]
+    pub model: String,
+    #[lens(skip)]
+    pub wheels: u8,
+}
+
+#[derive(Lens)]
+pub struct Garage<T>(pub Vec<T>, pub Car);
+
+#[test]
+fn test_derived_lenses() {
+    let car = Car { engine: Engine { power: 100 }, model: "a".to_owned(), wheels: 4 };
+    let car = car.set((
+        Car::ENGINE.modify_with(ModifyPower(150)),
+        Car::NAME.modify_with(::modifier::modify_with(|model: &mut String| model.push('b'))),
+    ));
+    assert_eq!(car.engine.power, 150);
+    assert_eq!(car.model, "ab");
+    assert_eq!(*Car::ENGINE.compose(Engine::POWER).view(&car), 150);
+}
+
+#[test]
+fn test_nested_lenses() {
+    let car = Car { engine: Engine { power: 100 }, model: "a".to_owned(), wheels: 4 };
+    let mut garage = Garage(vec![1], car);
+    let lens = Garage::<i32>::FIELD_1.compose(Car::ENGINE);
+    ::modifier::Modifier::modify(lens.modify_with(ModifyPower(7)), &mut garage);
+    assert_eq!(garage.1.engine.power, 7);
+    assert_eq!(Garage::<i32>::FIELD_0.view(&garage).len(), 1);
+}
@@ -0,0 +1,128 @@
+extern crate modifier;
+
+use std::marker::PhantomData;
+
+use modifier::{Diff, Merge, Patch, Patchable, Set};
+
+#[derive(Debug, Clone, PartialEq, Patch, Diff)]
+#[patch(derive(Debug, Clone, PartialEq))]
+pub struct Limits {
+    pub min: u32,
+    pub max: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Patch, Diff)]
+#[patch(name = "ServerUpdate")]
+pub struct Server {
+    pub host: String,
+    #[patch(nested)]
+    pub limits: Limits,
+    #[patch(skip)]
+    pub id: u64,
+}
+
+#[derive(Patch, Diff)]
+pub struct Pair<T>(T, u8);
+
+#[derive(Patch, Diff)]
+#[patch(derive(Debug, PartialEq))]
+pub struct Tagged(#[patch(skip)] u8, String, #[patch(nested)] Limits);
+
+#[derive(Debug, PartialEq, Patch, Diff)]
+pub struct Tracked<'a, T: ?Sized> {
+    pub count: u32,
+    #[patch(skip)]
+    pub source: PhantomData<&'a T>,
+}
+
+#[derive(Patch)]
+pub struct Wrapped<T>(pub u8, #[patch(skip)] pub PhantomData<T>);
+
+impl Set for Server {}
+
+#[test]
+fn test_patch_overwrites_present_fields() {
+    let server = Server { host: "a".to_owned(), limits: Limits { min: 1, max: 2 }, id: 7 };
+    let server = server.set(ServerUpdate {
+        host: None,
+        limits: Some(LimitsPatch { min: None, max: Some(10) }),
+    });
+    assert_eq!(server.host, "a");
+    assert_eq!(server.limits, Limits { min: 1, max: 10 });
+    assert_eq!(server.id, 7);
+
+    let server = server.set(Some(<Server as Patchable>::Patch::default()));
+    assert_eq!(server.limits, Limits { min: 1, max: 10 });
+}
+
+#[test]
+fn test_patch_merge() {
+    let first = LimitsPatch { min: Some(1), max: Some(2) };
+    let second = LimitsPatch { min: None, max: Some(3) };
+    assert_eq!(first.clone().merge(second), LimitsPatch { min: Some(1), max: Some(3) });
+
+    let update = ServerUpdate {
+        host: Some("b".to_owned()),
+        limits: Some(first),
+    }.merge(ServerUpdate {
+        host: None,
+        limits: Some(LimitsPatch { min: Some(5), max: None }),
+    });
+    let server = Server { host: "a".to_owned(), limits: Limits { min: 1, max: 2 }, id: 7 };
+    let server = server.set(update);
+    assert_eq!(server.host, "b");
+    assert_eq!(server.limits, Limits { min: 5, max: 2 });
+}
+
+#[test]
+fn test_tuple_patch() {
+    let mut pair = Pair("a", 1);
+    modifier::Modifier::modify(PairPatch(Some("b"), None), &mut pair);
+    assert_eq!((pair.0, pair.1), ("b", 1));
+}
+
+#[test]
+fn test_tuple_patch_with_skipped_field() {
+    let mut tagged = Tagged(1, "a".to_owned(), Limits { min: 1, max: 2 });
+    let patch = TaggedPatch(None, Some(LimitsPatch { min: Some(0), max: None }))
+        .merge(TaggedPatch(Some("b".to_owned()), None));
+    assert_eq!(patch, TaggedPatch(Some("b".to_owned()), Some(LimitsPatch { min: Some(0), max: None })));
+
+    modifier::Modifier::modify(patch, &mut tagged);
+    assert_eq!((tagged.0, tagged.1.as_str(), &tagged.2), (1, "b", &Limits { min: 0, max: 2 }));
+    assert_eq!(TaggedPatch::default(), TaggedPatch(None, None));
+
+    let new = Tagged(2, "c".to_owned(), Limits { min: 0, max: 2 });
+    assert_eq!(tagged.diff(&new), TaggedPatch(Some("c".to_owned()), None));
+}
+
+#[test]
+fn test_patch_with_skipped_generic_field() {
+    let mut tracked = Tracked::<str> { count: 1, source: PhantomData };
+    let patch = TrackedPatch::default().merge(TrackedPatch { count: Some(2), _marker: PhantomData });
+    modifier::Modifier::modify(patch, &mut tracked);
+    assert_eq!(tracked.count, 2);
+
+    let new = Tracked { count: 3, source: PhantomData };
+    assert_eq!(tracked.diff(&new).count, Some(3));
+
+    let mut wrapped = Wrapped::<String>(1, PhantomData);
+    modifier::Modifier::modify(WrappedPatch(Some(2), PhantomData), &mut wrapped);
+    assert_eq!(wrapped.0, 2);
+}
+
+#[test]
+fn test_diff() {
+    let old = Server { host: "a".to_owned(), limits: Limits { min: 1, max: 2 }, id: 7 };
+    let mut new = old.clone();
+    new.limits.max = 20;
+
+    let diff = old.diff(&new);
+    assert!(diff.host.is_none());
+    assert_eq!(diff.limits, Some(LimitsPatch { min: None, max: Some(20) }));
+    assert_eq!(old.set(diff), new);
+
+    let pair = Pair(vec![1], 2);
+    let diff = pair.diff(&Pair(vec![3], 2));
+    assert_eq!((diff.0, diff.1), (Some(vec![3]), None));
+}
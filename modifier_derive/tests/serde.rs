@@ -0,0 +1,50 @@
+extern crate modifier;
+#[macro_use]
+extern crate serde;
+extern crate serde_json;
+
+use modifier::{Patch, Set};
+
+#[derive(Debug, PartialEq, Set, Patch)]
+#[modifier(derive(Debug, PartialEq, Serialize, Deserialize))]
+#[patch(derive(Debug, PartialEq, Serialize, Deserialize))]
+pub struct Account {
+    #[modifier(into)]
+    pub name: String,
+    pub balance: i64,
+}
+
+#[test]
+fn test_serialized_field_modifiers() {
+    let chain = (ModifyName("b".to_owned()), Some(ModifyBalance(10)));
+    let json = serde_json::to_string(&chain).unwrap();
+    assert_eq!(json, r#"[{"name":"b"},{"balance":10}]"#);
+
+    let chain: (ModifyName<String>, Option<ModifyBalance>) = serde_json::from_str(&json).unwrap();
+    let account = Account { name: "a".to_owned(), balance: 0 }.set(chain);
+    assert_eq!(account, Account { name: "b".to_owned(), balance: 10 });
+
+    assert!(serde_json::from_str::<ModifyBalance>(r#"{"name":"b"}"#).is_err());
+}
+
+#[test]
+fn test_serialized_modifier_list() {
+    let modifiers = vec![AccountModifier::Balance(10), AccountModifier::Name("b".to_owned())];
+    let json = serde_json::to_string(&modifiers).unwrap();
+    assert_eq!(json, r#"[{"balance":10},{"name":"b"}]"#);
+
+    let modifiers: Vec<AccountModifier> =
+        serde_json::from_str(r#"[{"name":"b"}, {"balance":1}, {"balance":2}]"#).unwrap();
+    let account = Account { name: "a".to_owned(), balance: 0 }.set(modifiers);
+    assert_eq!(account, Account { name: "b".to_owned(), balance: 2 });
+}
+
+#[test]
+fn test_serialized_patch() {
+    let patch = AccountPatch { name: None, balance: Some(5) };
+    let json = serde_json::to_string(&patch).unwrap();
+    assert_eq!(json, r#"{"balance":5}"#);
+
+    let patch: AccountPatch = serde_json::from_str(&json).unwrap();
+    assert_eq!(Account { name: "a".to_owned(), balance: 0 }.set(patch).balance, 5);
+}
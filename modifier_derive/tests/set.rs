@@ -0,0 +1,107 @@
+extern crate modifier;
+
+use std::marker::PhantomData;
+
+use modifier::Set;
+
+#[derive(Set)]
+pub struct Thing {
+    x: usize,
+    name: String,
+}
+
+#[derive(Set)]
+#[modifier(prefix = "Change", derive(Debug, Clone, PartialEq))]
+pub struct Config {
+    #[modifier(name = "Retries")]
+    retries: u32,
+    #[modifier(into)]
+    host: String,
+    #[modifier(skip)]
+    #[allow(dead_code)]
+    locked: bool,
+}
+
+#[derive(Set)]
+pub struct Pair(u8, u16);
+
+#[derive(Set)]
+pub struct Wrapper<T> {
+    inner: Vec<T>,
+    #[modifier(into)]
+    label: String,
+}
+
+#[derive(Set)]
+#[modifier(enum_name = "TrackedChange")]
+pub struct Tracked<T> {
+    count: u32,
+    #[modifier(skip)]
+    #[allow(dead_code)]
+    source: PhantomData<T>,
+}
+
+#[test]
+fn test_field_modifiers() {
+    let thing = Thing { x: 1, name: "a".to_owned() }
+        .set((ModifyX(2), ModifyName("b".to_owned())));
+    assert_eq!(thing.x, 2);
+    assert_eq!(thing.name, "b");
+}
+
+#[test]
+fn test_container_and_field_attributes() {
+    let mut config = Config { retries: 0, host: String::new(), locked: true };
+    config.set_mut(Retries(3)).set_mut(ChangeHost("localhost"));
+    assert_eq!(config.retries, 3);
+    assert_eq!(config.host, "localhost");
+    assert_eq!(Retries(1).clone(), Retries(1));
+}
+
+#[test]
+fn test_tuple_struct() {
+    let pair = Pair(1, 2).set((Modify0(3), Modify1(4)));
+    assert_eq!((pair.0, pair.1), (3, 4));
+}
+
+#[test]
+fn test_generic_struct() {
+    let wrapper = Wrapper { inner: vec![1], label: String::new() }
+        .set((ModifyInner(vec![2, 3]), ModifyLabel("numbers")));
+    assert_eq!(wrapper.inner, vec![2, 3]);
+    assert_eq!(wrapper.label, "numbers");
+}
+
+#[test]
+fn test_reversible_field_modifiers() {
+    let mut config = Config { retries: 0, host: "a".to_owned(), locked: false };
+    let undo = config.set_reversible((Retries(3), ChangeHost("b")));
+    assert_eq!((config.retries, &*config.host), (3, "b"));
+
+    config.set_mut(undo);
+    assert_eq!((config.retries, &*config.host), (0, "a"));
+
+    let mut wrapper = Wrapper { inner: vec![1], label: String::new() };
+    let undo = wrapper.set_reversible(ModifyInner(vec![2]));
+    wrapper.set_mut(undo);
+    assert_eq!(wrapper.inner, vec![1]);
+}
+
+#[test]
+fn test_modifier_enum() {
+    let mut thing = Thing { x: 1, name: "a".to_owned() };
+    thing.set_mut(vec![ThingModifier::X(2), ThingModifier::Name("b".to_owned())]);
+    assert_eq!((thing.x, &*thing.name), (2, "b"));
+
+    let undo = thing.set_reversible(vec![ThingModifier::X(3), ThingModifier::X(4)]);
+    assert_eq!(thing.x, 4);
+    thing.set_mut(undo);
+    assert_eq!(thing.x, 2);
+
+    let pair = Pair(1, 2).set(PairModifier::Field1(5));
+    assert_eq!((pair.0, pair.1), (1, 5));
+    assert_eq!(ConfigModifier::Retries(1).clone(), ConfigModifier::Retries(1));
+
+    let tracked = Tracked::<String> { count: 0, source: PhantomData }.set(TrackedChange::Count(1));
+    assert_eq!(tracked.count, 1);
+}
@@ -0,0 +1,44 @@
+//! A version of `Set` implemented for every type.
+//!
+//! `modifier::Set` has to be implemented for a type before its methods
+//! can be used, which is impossible for foreign types without a `Set` impl
+//! of their own. Importing `modifier::blanket::Set` in its place makes the
+//! same methods available on every type:
+//!
+//! ```
+//! use modifier::blanket::Set;
+//! # use modifier::modify_with;
+//!
+//! let pair = (1, 2).set(modify_with(|pair: &mut (usize, usize)| pair.0 += 1));
+//! assert_eq!(pair, (2, 2));
+//! ```
+//!
+//! Both traits provide the same methods, so only one of them should be in
+//! scope at a time.
+
+/// Provides the methods of `modifier::Set` for all types.
+pub trait Set {
+    set_methods!();
+}
+
+impl<T: ?Sized> Set for T {}
+
+#[cfg(test)]
+mod test {
+    use super::Set;
+    use {modify_with, At};
+
+    #[test]
+    fn test_blanket_set() {
+        let mut pair = (1, 2).set(modify_with(|pair: &mut (usize, usize)| pair.0 += 1));
+        assert_eq!(pair, (2, 2));
+
+        let bump = |pair: &mut (usize, usize)| pair.1 *= 5;
+        pair.set_mut(modify_with(bump)).set_mut(modify_with(bump));
+        assert_eq!(pair, (2, 50));
+
+        let mut things = [1, 2, 3];
+        things[..].set_mut(At(1, modify_with(|x: &mut usize| *x = 7)));
+        assert_eq!(things, [1, 7, 3]);
+    }
+}
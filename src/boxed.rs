@@ -0,0 +1,90 @@
+use std::fmt;
+
+use Modifier;
+
+/// A type-erased modifier of `F`.
+///
+/// Allows modifiers of different types to be stored together, for instance
+/// in a `Vec<BoxModifier<F>>` assembled at runtime. The erased modifier
+/// cannot be recovered, so a `BoxModifier` cannot be serialized; the enum
+/// generated by `#[derive(Set)]` is a serializable alternative for the
+/// fields of a struct.
+pub struct BoxModifier<'a, F: ?Sized>(Box<dyn FnOnce(&mut F) + 'a>);
+
+impl<'a, F: ?Sized> BoxModifier<'a, F> {
+    /// Erase the type of `modifier`.
+    pub fn new<M: Modifier<F> + 'a>(modifier: M) -> BoxModifier<'a, F> {
+        BoxModifier(Box::new(move |f: &mut F| modifier.modify(f)))
+    }
+}
+
+impl<'a, F: ?Sized> Modifier<F> for BoxModifier<'a, F> {
+    fn modify(self, f: &mut F) {
+        (self.0)(f)
+    }
+}
+
+impl<'a, F: ?Sized> fmt::Debug for BoxModifier<'a, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("BoxModifier { .. }")
+    }
+}
+
+/// A type-erased modifier of `F` which can be sent between threads.
+pub struct SendBoxModifier<'a, F: ?Sized>(Box<dyn FnOnce(&mut F) + Send + 'a>);
+
+impl<'a, F: ?Sized> SendBoxModifier<'a, F> {
+    /// Erase the type of `modifier`.
+    pub fn new<M: Modifier<F> + Send + 'a>(modifier: M) -> SendBoxModifier<'a, F> {
+        SendBoxModifier(Box::new(move |f: &mut F| modifier.modify(f)))
+    }
+}
+
+impl<'a, F: ?Sized> Modifier<F> for SendBoxModifier<'a, F> {
+    fn modify(self, f: &mut F) {
+        (self.0)(f)
+    }
+}
+
+impl<'a, F: ?Sized> fmt::Debug for SendBoxModifier<'a, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SendBoxModifier { .. }")
+    }
+}
+
+impl<'a, F: ?Sized> From<SendBoxModifier<'a, F>> for BoxModifier<'a, F> {
+    fn from(modifier: SendBoxModifier<'a, F>) -> BoxModifier<'a, F> {
+        BoxModifier(modifier.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+    use std::collections::VecDeque;
+    use std::thread;
+
+    #[test]
+    fn test_runtime_modifier_lists() {
+        let modifiers: Vec<BoxModifier<Thing>> = vec![
+            BoxModifier::new(ModifyX(3)),
+            Double.boxed(),
+            SendBoxModifier::new(Double).into(),
+        ];
+        let thing = Thing { x: 1 }.set(modifiers);
+        assert_eq!(thing.x, 12);
+
+        let mut modifiers = VecDeque::new();
+        modifiers.push_back(Double.boxed());
+        modifiers.push_front(Box::new(ModifyX(1)).boxed());
+        let thing = thing.set(modifiers);
+        assert_eq!(thing.x, 2);
+    }
+
+    #[test]
+    fn test_send_box_modifier() {
+        let modifier = SendBoxModifier::new((ModifyX(3), Double));
+        let thing = thread::spawn(move || Thing { x: 1 }.set(modifier)).join().unwrap();
+        assert_eq!(thing.x, 6);
+    }
+}
@@ -0,0 +1,109 @@
+//! Adapters for using closures as modifiers.
+//!
+//! A blanket `Modifier<F>` impl for every `FnOnce(&mut F)` would overlap
+//! with the impls for `Box<M>` and the other std types in this crate, so
+//! closures are wrapped in `ModifyWith` instead.
+
+use {Modifier, ModifierMut, ModifierRef, TryModifier};
+
+/// A closure used as a modifier, created by modify_with and try_modify_with.
+#[derive(Debug, Clone, Copy)]
+pub struct ModifyWith<C>(C);
+
+/// Use `modify` as a `Modifier`.
+///
+/// The argument of the closure usually needs a type annotation:
+///
+/// ```
+/// # use modifier::{modify_with, Set};
+/// # struct Thing { x: usize }
+/// # impl Set for Thing {}
+/// # let thing = Thing { x: 1 };
+/// let thing = thing.set(modify_with(|thing: &mut Thing| thing.x += 1));
+/// # assert_eq!(thing.x, 2);
+/// ```
+///
+/// The result is also a `ModifierMut` or `ModifierRef` when `modify` is
+/// `FnMut` or `Fn`. A closure written inline in the call is inferred to be
+/// only `FnOnce`, so bind it to a variable first to reuse it.
+pub fn modify_with<F: ?Sized, C: FnOnce(&mut F)>(modify: C) -> ModifyWith<C> {
+    ModifyWith(modify)
+}
+
+/// Use the fallible `modify` as a `TryModifier`.
+pub fn try_modify_with<F: ?Sized, E, C>(modify: C) -> ModifyWith<C>
+where C: FnOnce(&mut F) -> Result<(), E> {
+    ModifyWith(modify)
+}
+
+impl<F: ?Sized, C> Modifier<F> for ModifyWith<C>
+where C: FnOnce(&mut F) {
+    fn modify(self, f: &mut F) {
+        (self.0)(f)
+    }
+}
+
+impl<F: ?Sized, E, C> TryModifier<F> for ModifyWith<C>
+where C: FnOnce(&mut F) -> Result<(), E> {
+    type Error = E;
+
+    fn try_modify(self, f: &mut F) -> Result<(), E> {
+        (self.0)(f)
+    }
+}
+
+impl<F: ?Sized, C> ModifierMut<F> for ModifyWith<C>
+where C: FnMut(&mut F) {
+    fn modify_mut(&mut self, f: &mut F) {
+        (self.0)(f)
+    }
+}
+
+impl<F: ?Sized, C> ModifierRef<F> for ModifyWith<C>
+where C: Fn(&mut F) {
+    fn modify_ref(&self, f: &mut F) {
+        (self.0)(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+
+    #[test]
+    fn test_modify_with() {
+        let thing = Thing { x: 1 }.set(modify_with(|thing: &mut Thing| thing.x += 1));
+        assert_eq!(thing.x, 2);
+
+        let increment = |thing: &mut Thing| thing.x += 1;
+        let thing = thing.set((ModifyX(5), Some(modify_with(increment)), modify_with(increment)));
+        assert_eq!(thing.x, 7);
+    }
+
+    #[test]
+    fn test_reusable_closures() {
+        let mut calls = 0;
+        let count = |thing: &mut Thing| {
+            calls += 1;
+            thing.x += 1;
+        };
+        let mut count = modify_with(count);
+        let mut thing = Thing { x: 0 };
+        thing.set_mut(&mut count).set_mut(&mut count);
+        assert_eq!((thing.x, calls), (2, 2));
+
+        let increment = |thing: &mut Thing| thing.x += 1;
+        let increment = modify_with(increment);
+        let thing = thing.set_ref(&increment).set((&increment, Some(&increment)));
+        assert_eq!(thing.x, 5);
+    }
+
+    #[test]
+    fn test_try_modify_with() {
+        let mut thing = Thing { x: 1 };
+        let error = thing.try_set_mut(try_modify_with(|thing: &mut Thing| {
+            if thing.x == 1 { Err("one") } else { Ok(()) }
+        }));
+        assert_eq!(error.err(), Some("one"));
+    }
+}
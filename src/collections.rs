@@ -0,0 +1,354 @@
+//! Modifiers for the elements of collections.
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+use error::link;
+use {ChainError, Modifier, TryModifier};
+
+/// Applies a modifier to the element at an index of a `Vec`, `VecDeque` or slice.
+///
+/// Does nothing if the index is out of bounds; as a `TryModifier` it
+/// reports the missing index instead.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct At<M>(pub usize, pub M);
+
+/// Applies a modifier to the value at a key of a `HashMap` or `BTreeMap`.
+///
+/// The key is borrowed, as in `HashMap::get_mut`, so `AtKey("a", m)` works
+/// on a map with `String` keys. Does nothing if the key is missing; as a
+/// `TryModifier` it reports the missing key instead.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct AtKey<K, M>(pub K, pub M);
+
+/// Applies a modifier to the value at a key of a `HashMap` or `BTreeMap`,
+/// first inserting the default value if the key is missing.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct Upsert<K, M>(pub K, pub M);
+
+/// Applies a clone of a modifier to every element of a collection.
+///
+/// Implemented for `Vec`, `VecDeque`, slices and the values of `HashMap`
+/// and `BTreeMap`; other collections can use `ForEach::modify_each` with
+/// any iterator of mutable references.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct ForEach<M>(pub M);
+
+/// Applies a clone of a modifier to every element of a collection which
+/// matches a predicate.
+///
+/// Supports the same collections as `ForEach`.
+#[derive(Debug, Clone, Copy)]
+pub struct Where<P, M>(pub P, pub M);
+
+impl<M> ForEach<M> {
+    /// Apply a clone of the modifier to every element yielded by `elements`.
+    pub fn modify_each<'a, T: 'a, I>(self, elements: I)
+    where I: IntoIterator<Item = &'a mut T>,
+          M: Modifier<T> + Clone {
+        for element in elements {
+            self.0.clone().modify(element);
+        }
+    }
+
+    /// Apply a clone of the fallible modifier to every element yielded by
+    /// `elements`, stopping at the first failure.
+    pub fn try_modify_each<'a, T: 'a, I>(self, elements: I) -> Result<(), ChainError<M::Error>>
+    where I: IntoIterator<Item = &'a mut T>,
+          M: TryModifier<T> + Clone {
+        for (index, element) in elements.into_iter().enumerate() {
+            link(index, self.0.clone().try_modify(element))?;
+        }
+        Ok(())
+    }
+}
+
+impl<P, M> Where<P, M> {
+    /// Apply a clone of the modifier to every element yielded by `elements`
+    /// which matches the predicate.
+    pub fn modify_each<'a, T: 'a, I>(mut self, elements: I)
+    where I: IntoIterator<Item = &'a mut T>,
+          P: FnMut(&T) -> bool,
+          M: Modifier<T> + Clone {
+        for element in elements {
+            if (self.0)(element) {
+                self.1.clone().modify(element);
+            }
+        }
+    }
+
+    /// Apply a clone of the fallible modifier to every element yielded by
+    /// `elements` which matches the predicate, stopping at the first failure.
+    ///
+    /// The index in a `ChainError` counts every element, matching or not.
+    pub fn try_modify_each<'a, T: 'a, I>(mut self, elements: I) -> Result<(), ChainError<M::Error>>
+    where I: IntoIterator<Item = &'a mut T>,
+          P: FnMut(&T) -> bool,
+          M: TryModifier<T> + Clone {
+        for (index, element) in elements.into_iter().enumerate() {
+            if (self.0)(element) {
+                link(index, self.1.clone().try_modify(element))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+macro_rules! each_impls {
+    ($([$($params:tt)*] $target:ty, $element:ident => $elements:expr;)+) => {$(
+        impl<$($params)*, M> Modifier<$target> for ForEach<M>
+        where M: Modifier<T> + Clone {
+            fn modify(self, $element: &mut $target) {
+                self.modify_each($elements);
+            }
+        }
+
+        impl<$($params)*, M> TryModifier<$target> for ForEach<M>
+        where M: TryModifier<T> + Clone {
+            type Error = ChainError<M::Error>;
+
+            fn try_modify(self, $element: &mut $target) -> Result<(), Self::Error> {
+                self.try_modify_each($elements)
+            }
+        }
+
+        impl<$($params)*, P, M> Modifier<$target> for Where<P, M>
+        where P: FnMut(&T) -> bool,
+              M: Modifier<T> + Clone {
+            fn modify(self, $element: &mut $target) {
+                self.modify_each($elements);
+            }
+        }
+
+        impl<$($params)*, P, M> TryModifier<$target> for Where<P, M>
+        where P: FnMut(&T) -> bool,
+              M: TryModifier<T> + Clone {
+            type Error = ChainError<M::Error>;
+
+            fn try_modify(self, $element: &mut $target) -> Result<(), Self::Error> {
+                self.try_modify_each($elements)
+            }
+        }
+    )+}
+}
+
+each_impls! {
+    [T] [T], slice => slice.iter_mut();
+    [T] Vec<T>, vec => vec.iter_mut();
+    [T] VecDeque<T>, deque => deque.iter_mut();
+    [K, T, S] HashMap<K, T, S>, map => map.values_mut();
+    [K, T] BTreeMap<K, T>, map => map.values_mut();
+}
+
+/// The error produced when an index or key is not present in a collection.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyError<K>(pub K);
+
+impl<K: fmt::Debug> fmt::Display for KeyError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no element at {:?}", self.0)
+    }
+}
+
+impl<K: fmt::Debug> Error for KeyError<K> {}
+
+fn try_at<T, M: Modifier<T>>(element: Option<&mut T>, modifier: M) -> Result<(), M> {
+    match element {
+        Some(element) => {
+            modifier.modify(element);
+            Ok(())
+        }
+        None => Err(modifier),
+    }
+}
+
+impl<T, M: Modifier<T>> Modifier<[T]> for At<M> {
+    fn modify(self, slice: &mut [T]) {
+        let _ = try_at(slice.get_mut(self.0), self.1);
+    }
+}
+
+impl<T, M: Modifier<T>> TryModifier<[T]> for At<M> {
+    type Error = KeyError<usize>;
+
+    fn try_modify(self, slice: &mut [T]) -> Result<(), KeyError<usize>> {
+        let At(index, modifier) = self;
+        try_at(slice.get_mut(index), modifier).map_err(|_| KeyError(index))
+    }
+}
+
+impl<T, M: Modifier<T>> Modifier<Vec<T>> for At<M> {
+    fn modify(self, vec: &mut Vec<T>) {
+        self.modify(&mut vec[..]);
+    }
+}
+
+impl<T, M: Modifier<T>> TryModifier<Vec<T>> for At<M> {
+    type Error = KeyError<usize>;
+
+    fn try_modify(self, vec: &mut Vec<T>) -> Result<(), KeyError<usize>> {
+        self.try_modify(&mut vec[..])
+    }
+}
+
+impl<T, M: Modifier<T>> Modifier<VecDeque<T>> for At<M> {
+    fn modify(self, deque: &mut VecDeque<T>) {
+        let _ = try_at(deque.get_mut(self.0), self.1);
+    }
+}
+
+impl<T, M: Modifier<T>> TryModifier<VecDeque<T>> for At<M> {
+    type Error = KeyError<usize>;
+
+    fn try_modify(self, deque: &mut VecDeque<T>) -> Result<(), KeyError<usize>> {
+        let At(index, modifier) = self;
+        try_at(deque.get_mut(index), modifier).map_err(|_| KeyError(index))
+    }
+}
+
+impl<K, V, S, Q: ?Sized, M> Modifier<HashMap<K, V, S>> for AtKey<&Q, M>
+where K: Borrow<Q> + Hash + Eq,
+      Q: Hash + Eq,
+      S: BuildHasher,
+      M: Modifier<V> {
+    fn modify(self, map: &mut HashMap<K, V, S>) {
+        let _ = try_at(map.get_mut(self.0), self.1);
+    }
+}
+
+impl<'q, K, V, S, Q: ?Sized, M> TryModifier<HashMap<K, V, S>> for AtKey<&'q Q, M>
+where K: Borrow<Q> + Hash + Eq,
+      Q: Hash + Eq,
+      S: BuildHasher,
+      M: Modifier<V> {
+    type Error = KeyError<&'q Q>;
+
+    fn try_modify(self, map: &mut HashMap<K, V, S>) -> Result<(), KeyError<&'q Q>> {
+        let AtKey(key, modifier) = self;
+        try_at(map.get_mut(key), modifier).map_err(|_| KeyError(key))
+    }
+}
+
+impl<K, V, Q: ?Sized, M> Modifier<BTreeMap<K, V>> for AtKey<&Q, M>
+where K: Borrow<Q> + Ord,
+      Q: Ord,
+      M: Modifier<V> {
+    fn modify(self, map: &mut BTreeMap<K, V>) {
+        let _ = try_at(map.get_mut(self.0), self.1);
+    }
+}
+
+impl<'q, K, V, Q: ?Sized, M> TryModifier<BTreeMap<K, V>> for AtKey<&'q Q, M>
+where K: Borrow<Q> + Ord,
+      Q: Ord,
+      M: Modifier<V> {
+    type Error = KeyError<&'q Q>;
+
+    fn try_modify(self, map: &mut BTreeMap<K, V>) -> Result<(), KeyError<&'q Q>> {
+        let AtKey(key, modifier) = self;
+        try_at(map.get_mut(key), modifier).map_err(|_| KeyError(key))
+    }
+}
+
+impl<K, V, S, M> Modifier<HashMap<K, V, S>> for Upsert<K, M>
+where K: Hash + Eq,
+      V: Default,
+      S: BuildHasher,
+      M: Modifier<V> {
+    fn modify(self, map: &mut HashMap<K, V, S>) {
+        self.1.modify(map.entry(self.0).or_default());
+    }
+}
+
+impl<K, V, M> Modifier<BTreeMap<K, V>> for Upsert<K, M>
+where K: Ord,
+      V: Default,
+      M: Modifier<V> {
+    fn modify(self, map: &mut BTreeMap<K, V>) {
+        self.1.modify(map.entry(self.0).or_default());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Clone)]
+    struct Increment;
+
+    impl Modifier<usize> for Increment {
+        fn modify(self, count: &mut usize) {
+            *count += 1;
+        }
+    }
+
+    #[test]
+    fn test_at() {
+        let mut things = vec![Thing { x: 1 }, Thing { x: 2 }];
+        At(1, ModifyX(5)).modify(&mut things);
+        At(2, ModifyX(5)).modify(&mut things);
+        assert_eq!(things[1].x, 5);
+
+        let error = At(2, ModifyX(5)).try_modify(&mut things).err();
+        assert_eq!(error, Some(KeyError(2)));
+
+        At(0, Double).modify(&mut things[..]);
+        assert_eq!(things[0].x, 2);
+    }
+
+    #[test]
+    fn test_for_each_and_where() {
+        let mut things = vec![Thing { x: 1 }, Thing { x: 2 }, Thing { x: 3 }];
+        ForEach(Double).modify(&mut things);
+        ForEach(ModifyX(1)).modify(&mut things[1..]);
+        assert_eq!(things.iter().map(|thing| thing.x).collect::<Vec<_>>(), [2, 1, 1]);
+
+        Where(|thing: &Thing| thing.x == 1, CheckedX(50)).try_modify(&mut things).unwrap();
+        assert_eq!(things.iter().map(|thing| thing.x).collect::<Vec<_>>(), [2, 50, 50]);
+
+        let error = ForEach(CheckedX(500)).try_modify(&mut things).unwrap_err();
+        assert_eq!((error.index, error.error), (0, 500));
+
+        let mut map = HashMap::new();
+        map.insert("a", Thing { x: 1 });
+        map.insert("b", Thing { x: 4 });
+        Where(|thing: &Thing| thing.x > 2, Double).modify(&mut map);
+        assert_eq!((map["a"].x, map["b"].x), (1, 8));
+
+        let mut counts = vec![0usize; 3];
+        ForEach(Increment).modify_each(counts.iter_mut().skip(1));
+        assert_eq!(counts, [0, 1, 1]);
+    }
+
+    #[test]
+    fn test_at_key_and_upsert() {
+        let mut map = HashMap::new();
+        map.insert("a", Thing { x: 1 });
+        AtKey("a", Double).modify(&mut map);
+        assert_eq!(map["a"].x, 2);
+
+        let error = AtKey("b", Double).try_modify(&mut map).err();
+        assert_eq!(error, Some(KeyError("b")));
+
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        (Upsert("a".to_owned(), Increment), Upsert("a".to_owned(), Increment)).modify(&mut counts);
+        AtKey("b", Increment).modify(&mut counts);
+        assert_eq!(counts["a"], 2);
+        assert!(!counts.contains_key("b"));
+
+        let mut names: HashMap<String, usize> = HashMap::new();
+        names.insert("a".to_owned(), 1);
+        AtKey("a", Increment).modify(&mut names);
+        assert_eq!(names["a"], 2);
+        assert_eq!(AtKey("b", Increment).try_modify(&mut names).err(), Some(KeyError("b")));
+    }
+}
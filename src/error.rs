@@ -0,0 +1,33 @@
+use std::error::Error;
+use std::fmt;
+
+/// The error produced when a modifier in a chain fails.
+///
+/// Chains stop at the first failing modifier, so `index` identifies the
+/// modifier which failed and every modifier after it was not applied.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainError<E> {
+    /// The position of the failing modifier in the chain.
+    pub index: usize,
+
+    /// The error produced by the failing modifier.
+    pub error: E,
+}
+
+impl<E: fmt::Display> fmt::Display for ChainError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "modifier {} in chain failed: {}", self.index, self.error)
+    }
+}
+
+impl<E: Error + 'static> Error for ChainError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Tag the result of the modifier at `index` in a chain with its position.
+pub fn link<E>(index: usize, result: Result<(), E>) -> Result<(), ChainError<E>> {
+    result.map_err(|error| ChainError { index, error })
+}
@@ -0,0 +1,257 @@
+//! Combinators for building modifiers out of other modifiers.
+
+use {BoxModifier, Modifier, TryModifier};
+
+/// Combinators available on every `Modifier`.
+///
+/// Every combinator returns a concrete type which is itself a modifier,
+/// so combined modifiers cost no more than writing the logic by hand.
+pub trait ModifierExt<F: ?Sized>: Modifier<F> + Sized {
+    /// Apply self, then `next`.
+    fn then<N: Modifier<F>>(self, next: N) -> Then<Self, N> {
+        Then { first: self, second: next }
+    }
+
+    /// Apply self only if `condition` is true.
+    fn when(self, condition: bool) -> When<Self> {
+        When { modifier: self, condition }
+    }
+
+    /// Apply self only if `predicate` holds for the target.
+    fn when_with<P: FnOnce(&F) -> bool>(self, predicate: P) -> WhenWith<Self, P> {
+        WhenWith { modifier: self, predicate }
+    }
+
+    /// Apply self `times` times, cloning it for every application but the last.
+    fn repeat(self, times: usize) -> Repeat<Self> where Self: Clone {
+        Repeat { modifier: self, times }
+    }
+
+    /// Call `inspect` with the target after applying self.
+    fn inspect<I: FnOnce(&F)>(self, inspect: I) -> Inspect<Self, I> {
+        Inspect { modifier: self, inspect }
+    }
+
+    /// Erase the type of self.
+    fn boxed<'a>(self) -> BoxModifier<'a, F> where Self: 'a {
+        BoxModifier::new(self)
+    }
+}
+
+impl<F: ?Sized, M: Modifier<F>> ModifierExt<F> for M {}
+
+/// Combinators available on every `TryModifier`.
+pub trait TryModifierExt<F: ?Sized>: TryModifier<F> + Sized {
+    /// If self fails, apply the modifier produced by `handler` from the error instead.
+    ///
+    /// The result is a `Modifier` if the fallback is a `Modifier`, and a
+    /// `TryModifier` if the fallback is a `TryModifier`.
+    fn or_else<H>(self, handler: H) -> OrElse<Self, H> {
+        OrElse { modifier: self, handler }
+    }
+
+    /// Convert the error produced by self using `map`.
+    fn map_err<G>(self, map: G) -> MapErr<Self, G> {
+        MapErr { modifier: self, map }
+    }
+}
+
+impl<F: ?Sized, M: TryModifier<F>> TryModifierExt<F> for M {}
+
+/// Applies two modifiers in sequence, created by ModifierExt::then.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<F: ?Sized, A, B> Modifier<F> for Then<A, B>
+where A: Modifier<F>,
+      B: Modifier<F> {
+    fn modify(self, f: &mut F) {
+        self.first.modify(f);
+        self.second.modify(f);
+    }
+}
+
+/// Conditionally applies a modifier, created by ModifierExt::when.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct When<M> {
+    modifier: M,
+    condition: bool,
+}
+
+impl<F: ?Sized, M: Modifier<F>> Modifier<F> for When<M> {
+    fn modify(self, f: &mut F) {
+        if self.condition {
+            self.modifier.modify(f);
+        }
+    }
+}
+
+/// Applies a modifier if a predicate holds, created by ModifierExt::when_with.
+#[derive(Debug, Clone, Copy)]
+pub struct WhenWith<M, P> {
+    modifier: M,
+    predicate: P,
+}
+
+impl<F: ?Sized, M, P> Modifier<F> for WhenWith<M, P>
+where M: Modifier<F>,
+      P: FnOnce(&F) -> bool {
+    fn modify(self, f: &mut F) {
+        if (self.predicate)(f) {
+            self.modifier.modify(f);
+        }
+    }
+}
+
+/// Applies a modifier several times, created by ModifierExt::repeat.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct Repeat<M> {
+    modifier: M,
+    times: usize,
+}
+
+impl<F: ?Sized, M> Modifier<F> for Repeat<M>
+where M: Modifier<F> + Clone {
+    fn modify(self, f: &mut F) {
+        if self.times == 0 { return }
+
+        for _ in 1..self.times {
+            self.modifier.clone().modify(f);
+        }
+        self.modifier.modify(f);
+    }
+}
+
+/// Observes the target after a modifier, created by ModifierExt::inspect.
+#[derive(Debug, Clone, Copy)]
+pub struct Inspect<M, I> {
+    modifier: M,
+    inspect: I,
+}
+
+impl<F: ?Sized, M, I> Modifier<F> for Inspect<M, I>
+where M: Modifier<F>,
+      I: FnOnce(&F) {
+    fn modify(self, f: &mut F) {
+        self.modifier.modify(f);
+        (self.inspect)(f);
+    }
+}
+
+/// Falls back to another modifier on error, created by TryModifierExt::or_else.
+#[derive(Debug, Clone, Copy)]
+pub struct OrElse<M, H> {
+    modifier: M,
+    handler: H,
+}
+
+impl<F: ?Sized, M, H, N> Modifier<F> for OrElse<M, H>
+where M: TryModifier<F>,
+      H: FnOnce(M::Error) -> N,
+      N: Modifier<F> {
+    fn modify(self, f: &mut F) {
+        if let Err(error) = self.modifier.try_modify(f) {
+            (self.handler)(error).modify(f);
+        }
+    }
+}
+
+impl<F: ?Sized, M, H, N> TryModifier<F> for OrElse<M, H>
+where M: TryModifier<F>,
+      H: FnOnce(M::Error) -> N,
+      N: TryModifier<F> {
+    type Error = N::Error;
+
+    fn try_modify(self, f: &mut F) -> Result<(), N::Error> {
+        match self.modifier.try_modify(f) {
+            Ok(()) => Ok(()),
+            Err(error) => (self.handler)(error).try_modify(f),
+        }
+    }
+}
+
+/// Converts the error of a modifier, created by TryModifierExt::map_err.
+#[derive(Debug, Clone, Copy)]
+pub struct MapErr<M, G> {
+    modifier: M,
+    map: G,
+}
+
+impl<F: ?Sized, M, G, E> TryModifier<F> for MapErr<M, G>
+where M: TryModifier<F>,
+      G: FnOnce(M::Error) -> E {
+    type Error = E;
+
+    fn try_modify(self, f: &mut F) -> Result<(), E> {
+        self.modifier.try_modify(f).map_err(self.map)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_then_and_when() {
+        let thing = Thing { x: 1 }.set(ModifyX(2).then(ModifyX(3)));
+        assert_eq!(thing.x, 3);
+
+        let thing = thing.set(ModifyX(4).when(false));
+        assert_eq!(thing.x, 3);
+
+        let thing = thing.set(ModifyX(4).when(true));
+        assert_eq!(thing.x, 4);
+    }
+
+    #[test]
+    fn test_when_with() {
+        let thing = Thing { x: 1 }.set(ModifyX(10).when_with(|thing| thing.x > 5));
+        assert_eq!(thing.x, 1);
+
+        let thing = thing.set(ModifyX(10).when_with(|thing| thing.x < 5));
+        assert_eq!(thing.x, 10);
+    }
+
+    #[test]
+    fn test_repeat_and_inspect() {
+        let seen = Cell::new(0);
+        let thing = Thing { x: 1 }
+            .set(Double.repeat(3).inspect(|thing: &Thing| seen.set(thing.x)));
+        assert_eq!(thing.x, 8);
+        assert_eq!(seen.get(), 8);
+
+        let thing = thing.set(Double.repeat(0));
+        assert_eq!(thing.x, 8);
+    }
+
+    #[test]
+    fn test_or_else_and_map_err() {
+        let thing = Thing { x: 1 }.set(CheckedX(500).or_else(|_| ModifyX(100)));
+        assert_eq!(thing.x, 100);
+
+        let mut thing = thing.try_set(CheckedX(500).or_else(|x| CheckedX(x / 10))).ok().unwrap();
+        assert_eq!(thing.x, 50);
+
+        let error = thing.try_set_mut(CheckedX(5000).or_else(|x| CheckedX(x / 10)).map_err(|x| x + 1));
+        assert_eq!(error.err(), Some(501));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use ext::{Then, When};
+        use serde_json;
+
+        let modifier = ModifyX(1).then(ModifyX(2)).when(true);
+        let json = serde_json::to_string(&modifier).unwrap();
+        let modifier: When<Then<ModifyX, ModifyX>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(Thing { x: 0 }.set(modifier).x, 2);
+    }
+}
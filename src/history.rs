@@ -0,0 +1,302 @@
+//! Undo and redo on top of reversible modifiers.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
+
+use {Modifier, ReversibleModifier, Set};
+
+/// A value which records the inverse of every modifier applied to it.
+///
+/// Modifiers are applied through `Set` by wrapping them in `Record`, or
+/// through History::record. Only reversible modifiers can be applied, and
+/// the value can only be read otherwise, so every change can be undone.
+///
+/// Recorded modifiers and their inverses are kept for the lifetime `'a`,
+/// so they may borrow data which outlives the history.
+pub struct History<'a, T> {
+    value: T,
+    undo: VecDeque<Step<'a, T>>,
+    redo: Vec<Step<'a, T>>,
+    limit: Option<usize>,
+    group: Option<Vec<Step<'a, T>>>,
+}
+
+impl<'a, T: 'a> History<'a, T> {
+    /// Start recording changes to `value`.
+    pub fn new(value: T) -> History<'a, T> {
+        History {
+            value,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit: None,
+            group: None,
+        }
+    }
+
+    /// Start recording changes to `value`, keeping at most `limit` undoable steps.
+    pub fn with_limit(value: T, limit: usize) -> History<'a, T> {
+        History { limit: Some(limit), ..History::new(value) }
+    }
+
+    /// Apply `modifier` to the value, recording its inverse.
+    ///
+    /// Recording a change discards every step which could have been redone.
+    pub fn record<M>(&mut self, modifier: M) -> &mut History<'a, T>
+    where M: ReversibleModifier<T> + 'a {
+        let step = Step::new(modifier.modify_reversible(&mut self.value));
+        match self.group {
+            Some(ref mut group) => group.push(step),
+            None => self.push(step),
+        }
+        self
+    }
+
+    /// Record every change made by `changes` as a single undoable step.
+    ///
+    /// Groups may be nested, in which case the outermost group becomes the
+    /// step. If `changes` panics, the changes it made before panicking are
+    /// still recorded, so they can be undone once the panic is caught.
+    pub fn group<R, G>(&mut self, changes: G) -> R
+    where G: FnOnce(&mut History<'a, T>) -> R {
+        let outer = self.group.replace(Vec::new());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| changes(self)));
+        let steps = self.group.take().unwrap_or_default();
+
+        match outer {
+            Some(mut outer) => {
+                outer.extend(steps);
+                self.group = Some(outer);
+            }
+            None if !steps.is_empty() => self.push(Step::group(steps)),
+            None => (),
+        }
+        result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+
+    /// Undo the most recent step, returning false if there was nothing to undo.
+    ///
+    /// Steps cannot be undone inside a group, so this also returns false there.
+    pub fn undo(&mut self) -> bool {
+        if self.group.is_some() { return false }
+        match self.undo.pop_back() {
+            Some(step) => {
+                let redo = step.apply(&mut self.value);
+                self.redo.push(redo);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the most recently undone step, returning false if there was nothing to redo.
+    ///
+    /// Steps cannot be redone inside a group, so this also returns false there.
+    pub fn redo(&mut self) -> bool {
+        if self.group.is_some() { return false }
+        match self.redo.pop() {
+            Some(step) => {
+                let undo = step.apply(&mut self.value);
+                self.undo.push_back(undo);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether there is a step to undo.
+    pub fn can_undo(&self) -> bool {
+        self.group.is_none() && !self.undo.is_empty()
+    }
+
+    /// Whether there is a step to redo.
+    pub fn can_redo(&self) -> bool {
+        self.group.is_none() && !self.redo.is_empty()
+    }
+
+    /// Forget every recorded step, keeping the current value.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Get the current value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Stop recording, returning the current value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    fn push(&mut self, step: Step<'a, T>) {
+        self.redo.clear();
+        self.undo.push_back(step);
+        if let Some(limit) = self.limit {
+            while self.undo.len() > limit {
+                self.undo.pop_front();
+            }
+        }
+    }
+}
+
+impl<'a, T> Deref for History<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for History<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("History")
+            .field("value", &self.value)
+            .field("undo", &self.undo.len())
+            .field("redo", &self.redo.len())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<'a, T> Set for History<'a, T> {}
+
+/// Applies a reversible modifier to a `History`, recording its inverse.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct Record<M>(pub M);
+
+impl<'a, T: 'a, M> Modifier<History<'a, T>> for Record<M>
+where M: ReversibleModifier<T> + 'a {
+    fn modify(self, history: &mut History<'a, T>) {
+        history.record(self.0);
+    }
+}
+
+/// A type-erased change which, when applied, produces the change undoing it.
+struct Step<'a, T>(Box<Apply<'a, T>>);
+
+type Apply<'a, T> = dyn FnOnce(&mut T) -> Step<'a, T> + 'a;
+
+impl<'a, T: 'a> Step<'a, T> {
+    fn new<M>(modifier: M) -> Step<'a, T>
+    where M: ReversibleModifier<T> + 'a {
+        Step(Box::new(move |target: &mut T| Step::new(modifier.modify_reversible(target))))
+    }
+
+    /// Steps are undone in the reverse of the order they were applied.
+    fn group(steps: Vec<Step<'a, T>>) -> Step<'a, T> {
+        Step(Box::new(move |target: &mut T| {
+            Step::group(steps.into_iter().rev().map(|step| step.apply(target)).collect())
+        }))
+    }
+
+    fn apply(self, target: &mut T) -> Step<'a, T> {
+        (self.0)(target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut history = History::new(Thing { x: 1 });
+        history.set_mut(Record(ModifyX(2))).set_mut(Record(ModifyX(3)));
+        assert_eq!(history.x, 3);
+
+        assert!(history.undo());
+        assert_eq!(history.x, 2);
+        assert!(history.undo());
+        assert_eq!(history.x, 1);
+        assert!(!history.undo());
+
+        assert!(history.redo());
+        assert_eq!(history.x, 2);
+
+        history.record((ModifyX(4), Some(ModifyX(5))));
+        assert!(!history.can_redo());
+        assert!(history.undo());
+        assert_eq!(history.x, 2);
+        assert!(history.redo());
+        assert_eq!(history.x, 5);
+    }
+
+    #[test]
+    fn test_groups() {
+        let mut history = History::new(Thing { x: 1 });
+        history.group(|history| {
+            history.set_mut(Record(ModifyX(2)));
+            history.group(|history| {
+                history.record(ModifyX(3)).record(ModifyX(4));
+            });
+        });
+        assert_eq!(history.x, 4);
+
+        assert!(history.undo());
+        assert_eq!(history.x, 1);
+        assert!(!history.can_undo());
+
+        assert!(history.redo());
+        assert_eq!(history.x, 4);
+    }
+
+    #[test]
+    fn test_panicking_group() {
+        let mut history = History::new(Thing { x: 1 });
+        let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+            history.group(|history| {
+                history.record(ModifyX(2));
+                assert!(!history.undo());
+                panic!("interrupted");
+            });
+        }));
+        assert!(result.is_err());
+
+        history.record(ModifyX(3));
+        assert!(history.undo());
+        assert_eq!(history.x, 2);
+        assert!(history.undo());
+        assert_eq!(history.x, 1);
+    }
+
+    #[test]
+    fn test_borrowed_modifiers() {
+        struct Name<'n>(&'n str);
+
+        impl<'n> Modifier<String> for Name<'n> {
+            fn modify(self, name: &mut String) {
+                *name = self.0.to_owned();
+            }
+        }
+
+        impl<'n> ReversibleModifier<String> for Name<'n> {
+            type Inverse = Replace<String>;
+
+            fn modify_reversible(self, name: &mut String) -> Replace<String> {
+                Replace(::std::mem::replace(name, self.0.to_owned()))
+            }
+        }
+
+        let new = String::from("b");
+        let mut history = History::new(String::from("a"));
+        history.record(Name(&new));
+        assert_eq!(*history, "b");
+        assert!(history.undo());
+        assert_eq!(*history, "a");
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut history = History::with_limit(Thing { x: 0 }, 2);
+        history.record(ModifyX(1)).record(ModifyX(2)).record(ModifyX(3));
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert!(!history.undo());
+        assert_eq!(history.into_inner().x, 1);
+    }
+}
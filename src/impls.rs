@@ -1,87 +1,436 @@
-/// Some implementations for chains of tuples.
-///
-/// FIXME(reem): Move generation of this to a build script.
+//! Some implementations for chains of tuples and other std types, and
+//! `Set` for the common std types.
+//!
+//! Tuple impls are generated by `tuples!` for every arity up to 16, or 32
+//! with the `large-tuples` feature.
 
-use Modifier;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::convert::Infallible;
 
-impl<X, M1> Modifier<X> for (M1,)
-where M1: Modifier<X> {
-    fn modify(self, x: &mut X) {
-        self.0.modify(x);
+use error::link;
+use {ChainError, Modifier, ModifierMut, ModifierOut, ModifierRef, ReversibleModifier, Set, Transform, TryModifier};
+
+macro_rules! set_impls {
+    ($([$($params:tt)*] $ty:ty,)+) => {
+        $(impl<$($params)*> Set for $ty {})+
+    };
+}
+
+set_impls! {
+    [] bool, [] char, [] String, [] str,
+    [] u8, [] u16, [] u32, [] u64, [] u128, [] usize,
+    [] i8, [] i16, [] i32, [] i64, [] i128, [] isize,
+    [] f32, [] f64,
+    [T] Option<T>,
+    [T] [T], [T, const N: usize] [T; N],
+    [T] Vec<T>, [T] VecDeque<T>, [T] LinkedList<T>, [T] BinaryHeap<T>,
+    [K, V, S] HashMap<K, V, S>, [K, V] BTreeMap<K, V>,
+    [T, S] HashSet<T, S>, [T] BTreeSet<T>,
+}
+
+impl<X: ?Sized> Modifier<X> for () {
+    fn modify(self, _: &mut X) {}
+}
+
+impl<X: ?Sized> TryModifier<X> for () {
+    type Error = Infallible;
+
+    fn try_modify(self, _: &mut X) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl<X: ?Sized> ReversibleModifier<X> for () {
+    type Inverse = ();
+
+    fn modify_reversible(self, _: &mut X) {}
+}
+
+impl<X> Transform<X> for () {
+    fn transform(self, x: X) -> X {
+        x
     }
 }
 
-impl<X, M1, M2> Modifier<X> for (M1, M2)
-where M1: Modifier<X>,
-      M2: Modifier<X> {
+impl<X: ?Sized> ModifierOut<X> for () {
+    type Output = ();
+
+    fn modify_out(self, _: &mut X) {}
+}
+
+impl<X: ?Sized> ModifierMut<X> for () {
+    fn modify_mut(&mut self, _: &mut X) {}
+}
+
+impl<X: ?Sized> ModifierRef<X> for () {
+    fn modify_ref(&self, _: &mut X) {}
+}
+
+impl<X: ?Sized, M: ?Sized> Modifier<X> for &M
+where M: ModifierRef<X> {
     fn modify(self, x: &mut X) {
-        self.0.modify(x);
-        self.1.modify(x);
+        self.modify_ref(x);
+    }
+}
+
+impl<X: ?Sized, M: ?Sized> ModifierMut<X> for &M
+where M: ModifierRef<X> {
+    fn modify_mut(&mut self, x: &mut X) {
+        self.modify_ref(x);
     }
 }
 
-impl<X, M1, M2, M3> Modifier<X> for (M1, M2, M3)
-where M1: Modifier<X>,
-      M2: Modifier<X>,
-      M3: Modifier<X> {
+impl<X: ?Sized, M: ?Sized> ModifierRef<X> for &M
+where M: ModifierRef<X> {
+    fn modify_ref(&self, x: &mut X) {
+        (**self).modify_ref(x);
+    }
+}
+
+impl<X: ?Sized, M: ?Sized> Modifier<X> for &mut M
+where M: ModifierMut<X> {
     fn modify(self, x: &mut X) {
-        self.0.modify(x);
-        self.1.modify(x);
-        self.2.modify(x);
+        self.modify_mut(x);
+    }
+}
+
+impl<X: ?Sized, M: ?Sized> ModifierMut<X> for &mut M
+where M: ModifierMut<X> {
+    fn modify_mut(&mut self, x: &mut X) {
+        (**self).modify_mut(x);
     }
 }
 
-impl<X, M1, M2, M3, M4> Modifier<X> for (M1, M2, M3, M4)
-where M1: Modifier<X>,
-      M2: Modifier<X>,
-      M3: Modifier<X>,
-      M4: Modifier<X> {
+macro_rules! tuple_impls {
+    ([$($name:ident . $idx:tt,)+] [$($rev:ident . $rev_idx:tt,)+]) => {
+        impl<X, $($name),+> Modifier<X> for ($($name,)+)
+        where $($name: Modifier<X>),+ {
+            fn modify(self, x: &mut X) {
+                $(self.$idx.modify(x);)+
+            }
+        }
+
+        impl<X, E, $($name),+> TryModifier<X> for ($($name,)+)
+        where $($name: TryModifier<X, Error = E>),+ {
+            type Error = ChainError<E>;
+
+            fn try_modify(self, x: &mut X) -> Result<(), ChainError<E>> {
+                $(link($idx, self.$idx.try_modify(x))?;)+
+                Ok(())
+            }
+        }
+
+        impl<X, $($name),+> Transform<X> for ($($name,)+)
+        where $($name: Transform<X>),+ {
+            fn transform(self, x: X) -> X {
+                $(let x = self.$idx.transform(x);)+
+                x
+            }
+        }
+
+        /// The output of a tuple is the tuple of the outputs of its elements.
+        impl<X, $($name),+> ModifierOut<X> for ($($name,)+)
+        where $($name: ModifierOut<X>),+ {
+            type Output = ($($name::Output,)+);
+
+            fn modify_out(self, x: &mut X) -> Self::Output {
+                ($(self.$idx.modify_out(x),)+)
+            }
+        }
+
+        impl<X, $($name),+> ModifierMut<X> for ($($name,)+)
+        where $($name: ModifierMut<X>),+ {
+            fn modify_mut(&mut self, x: &mut X) {
+                $(self.$idx.modify_mut(x);)+
+            }
+        }
+
+        impl<X, $($name),+> ModifierRef<X> for ($($name,)+)
+        where $($name: ModifierRef<X>),+ {
+            fn modify_ref(&self, x: &mut X) {
+                $(self.$idx.modify_ref(x);)+
+            }
+        }
+
+        /// The inverse of a tuple undoes its elements in reverse order.
+        impl<X, $($name),+> ReversibleModifier<X> for ($($name,)+)
+        where $($name: ReversibleModifier<X>),+ {
+            type Inverse = ($($rev::Inverse,)+);
+
+            fn modify_reversible(self, x: &mut X) -> Self::Inverse {
+                let inverses = ($(self.$idx.modify_reversible(x),)+);
+                ($(inverses.$rev_idx,)+)
+            }
+        }
+    };
+}
+
+/// Invoke `tuple_impls!` for every prefix of the given list after `$done`,
+/// passing each prefix both in order and reversed.
+macro_rules! tuples {
+    ([$($done:tt)*] [$($rev:tt)*]) => {};
+    ([$($done:tt)*] [$($rev:tt)*] $name:ident . $idx:tt $(, $rest:ident . $rest_idx:tt)*) => {
+        tuple_impls!([$($done)* $name . $idx,] [$name . $idx, $($rev)*]);
+        tuples!([$($done)* $name . $idx,] [$name . $idx, $($rev)*] $($rest . $rest_idx),*);
+    };
+}
+
+tuples!([] [] M1 . 0, M2 . 1, M3 . 2, M4 . 3, M5 . 4, M6 . 5, M7 . 6, M8 . 7,
+           M9 . 8, M10 . 9, M11 . 10, M12 . 11, M13 . 12, M14 . 13, M15 . 14, M16 . 15);
+
+#[cfg(feature = "large-tuples")]
+tuples!([M1 . 0, M2 . 1, M3 . 2, M4 . 3, M5 . 4, M6 . 5, M7 . 6, M8 . 7,
+         M9 . 8, M10 . 9, M11 . 10, M12 . 11, M13 . 12, M14 . 13, M15 . 14, M16 . 15,]
+        [M16 . 15, M15 . 14, M14 . 13, M13 . 12, M12 . 11, M11 . 10, M10 . 9, M9 . 8,
+         M8 . 7, M7 . 6, M6 . 5, M5 . 4, M4 . 3, M3 . 2, M2 . 1, M1 . 0,]
+        M17 . 16, M18 . 17, M19 . 18, M20 . 19, M21 . 20, M22 . 21, M23 . 22, M24 . 23,
+        M25 . 24, M26 . 25, M27 . 26, M28 . 27, M29 . 28, M30 . 29, M31 . 30, M32 . 31);
+
+impl<X, M> Modifier<X> for Option<M>
+where M: Modifier<X> {
     fn modify(self, x: &mut X) {
-        self.0.modify(x);
-        self.1.modify(x);
-        self.2.modify(x);
-        self.3.modify(x);
+        if let Some(m) = self {
+            m.modify(x);
+        }
+    }
+}
+
+impl<X, M> TryModifier<X> for Option<M>
+where M: TryModifier<X> {
+    type Error = M::Error;
+
+    fn try_modify(self, x: &mut X) -> Result<(), M::Error> {
+        match self {
+            Some(m) => m.try_modify(x),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<X, M, E> TryModifier<X> for Result<M, E>
+where M: Modifier<X> {
+    type Error = E;
+
+    fn try_modify(self, x: &mut X) -> Result<(), E> {
+        self.map(|m| m.modify(x))
+    }
+}
+
+impl<X, M> Transform<X> for Option<M>
+where M: Transform<X> {
+    fn transform(self, x: X) -> X {
+        match self {
+            Some(m) => m.transform(x),
+            None => x,
+        }
+    }
+}
+
+impl<X, M> ModifierOut<X> for Option<M>
+where M: ModifierOut<X> {
+    type Output = Option<M::Output>;
+
+    fn modify_out(self, x: &mut X) -> Option<M::Output> {
+        self.map(|m| m.modify_out(x))
+    }
+}
+
+impl<X, M> ModifierMut<X> for Option<M>
+where M: ModifierMut<X> {
+    fn modify_mut(&mut self, x: &mut X) {
+        if let Some(ref mut m) = *self {
+            m.modify_mut(x);
+        }
+    }
+}
+
+impl<X, M> ModifierRef<X> for Option<M>
+where M: ModifierRef<X> {
+    fn modify_ref(&self, x: &mut X) {
+        if let Some(ref m) = *self {
+            m.modify_ref(x);
+        }
     }
 }
 
-impl<X, M1, M2, M3, M4, M5> Modifier<X> for (M1, M2, M3, M4, M5)
-where M1: Modifier<X>,
-      M2: Modifier<X>,
-      M3: Modifier<X>,
-      M4: Modifier<X>,
-      M5: Modifier<X> {
+impl<X, M> ReversibleModifier<X> for Option<M>
+where M: ReversibleModifier<X> {
+    type Inverse = Option<M::Inverse>;
+
+    fn modify_reversible(self, x: &mut X) -> Option<M::Inverse> {
+        self.map(|m| m.modify_reversible(x))
+    }
+}
+
+impl<X, M> Modifier<X> for Vec<M>
+where M: Modifier<X> {
     fn modify(self, x: &mut X) {
-        self.0.modify(x);
-        self.1.modify(x);
-        self.2.modify(x);
-        self.3.modify(x);
-        self.4.modify(x);
+        for m in self {
+            m.modify(x);
+        }
+    }
+}
+
+impl<X, E, M> TryModifier<X> for Vec<M>
+where M: TryModifier<X, Error = E> {
+    type Error = ChainError<E>;
+
+    fn try_modify(self, x: &mut X) -> Result<(), ChainError<E>> {
+        for (index, m) in self.into_iter().enumerate() {
+            link(index, m.try_modify(x))?;
+        }
+        Ok(())
+    }
+}
+
+impl<X, M> Transform<X> for Vec<M>
+where M: Transform<X> {
+    fn transform(self, x: X) -> X {
+        self.into_iter().fold(x, |x, m| m.transform(x))
+    }
+}
+
+impl<X, M> ModifierOut<X> for Vec<M>
+where M: ModifierOut<X> {
+    type Output = Vec<M::Output>;
+
+    fn modify_out(self, x: &mut X) -> Vec<M::Output> {
+        self.into_iter().map(|m| m.modify_out(x)).collect()
+    }
+}
+
+impl<X, M> ModifierMut<X> for [M]
+where M: ModifierMut<X> {
+    fn modify_mut(&mut self, x: &mut X) {
+        for m in self {
+            m.modify_mut(x);
+        }
+    }
+}
+
+impl<X, M> ModifierRef<X> for [M]
+where M: ModifierRef<X> {
+    fn modify_ref(&self, x: &mut X) {
+        for m in self {
+            m.modify_ref(x);
+        }
+    }
+}
+
+impl<X, M> ModifierMut<X> for Vec<M>
+where M: ModifierMut<X> {
+    fn modify_mut(&mut self, x: &mut X) {
+        for m in self {
+            m.modify_mut(x);
+        }
     }
 }
 
-impl<X, M1, M2, M3, M4, M5, M6> Modifier<X> for (M1, M2, M3, M4, M5, M6)
-where M1: Modifier<X>,
-      M2: Modifier<X>,
-      M3: Modifier<X>,
-      M4: Modifier<X>,
-      M5: Modifier<X>,
-      M6: Modifier<X> {
+impl<X, M> ModifierRef<X> for Vec<M>
+where M: ModifierRef<X> {
+    fn modify_ref(&self, x: &mut X) {
+        for m in self {
+            m.modify_ref(x);
+        }
+    }
+}
+
+/// The inverse of a `Vec` undoes its elements in reverse order.
+impl<X, M> ReversibleModifier<X> for Vec<M>
+where M: ReversibleModifier<X> {
+    type Inverse = Vec<M::Inverse>;
+
+    fn modify_reversible(self, x: &mut X) -> Vec<M::Inverse> {
+        let mut inverses: Vec<_> = self.into_iter().map(|m| m.modify_reversible(x)).collect();
+        inverses.reverse();
+        inverses
+    }
+}
+
+impl<X, M> Modifier<X> for VecDeque<M>
+where M: Modifier<X> {
     fn modify(self, x: &mut X) {
-        self.0.modify(x);
-        self.1.modify(x);
-        self.2.modify(x);
-        self.3.modify(x);
-        self.4.modify(x);
-        self.5.modify(x);
+        for m in self {
+            m.modify(x);
+        }
     }
 }
 
-impl<X, M> Modifier<X> for Option<M>
+impl<X, E, M> TryModifier<X> for VecDeque<M>
+where M: TryModifier<X, Error = E> {
+    type Error = ChainError<E>;
+
+    fn try_modify(self, x: &mut X) -> Result<(), ChainError<E>> {
+        for (index, m) in self.into_iter().enumerate() {
+            link(index, m.try_modify(x))?;
+        }
+        Ok(())
+    }
+}
+
+impl<X, M, const N: usize> Modifier<X> for [M; N]
 where M: Modifier<X> {
     fn modify(self, x: &mut X) {
-        match self {
-            Some(m) => m.modify(x),
-            None => (),
+        for m in IntoIterator::into_iter(self) {
+            m.modify(x);
+        }
+    }
+}
+
+impl<X, E, M, const N: usize> TryModifier<X> for [M; N]
+where M: TryModifier<X, Error = E> {
+    type Error = ChainError<E>;
+
+    fn try_modify(self, x: &mut X) -> Result<(), ChainError<E>> {
+        for (index, m) in IntoIterator::into_iter(self).enumerate() {
+            link(index, m.try_modify(x))?;
         }
+        Ok(())
+    }
+}
+
+impl<X, M> Modifier<X> for Box<M>
+where M: Modifier<X> {
+    fn modify(self, x: &mut X) {
+        (*self).modify(x);
+    }
+}
+
+impl<X, M> TryModifier<X> for Box<M>
+where M: TryModifier<X> {
+    type Error = M::Error;
+
+    fn try_modify(self, x: &mut X) -> Result<(), M::Error> {
+        (*self).try_modify(x)
+    }
+}
+
+impl<X, M> Transform<X> for Box<M>
+where M: Transform<X> {
+    fn transform(self, x: X) -> X {
+        (*self).transform(x)
+    }
+}
+
+impl<X, M> ModifierOut<X> for Box<M>
+where M: ModifierOut<X> {
+    type Output = M::Output;
+
+    fn modify_out(self, x: &mut X) -> M::Output {
+        (*self).modify_out(x)
+    }
+}
+
+impl<X, M: ?Sized> ModifierMut<X> for Box<M>
+where M: ModifierMut<X> {
+    fn modify_mut(&mut self, x: &mut X) {
+        (**self).modify_mut(x);
+    }
+}
+
+impl<X, M: ?Sized> ModifierRef<X> for Box<M>
+where M: ModifierRef<X> {
+    fn modify_ref(&self, x: &mut X) {
+        (**self).modify_ref(x);
     }
 }
@@ -0,0 +1,92 @@
+use error::link;
+use {ChainError, Modifier, TryModifier};
+
+/// Applies every modifier produced by an iterator, in order.
+///
+/// Useful for modifiers which are computed lazily, where collecting
+/// them into a `Vec` first would be wasteful.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct Sequence<I>(pub I);
+
+impl<F: ?Sized, I> Modifier<F> for Sequence<I>
+where I: IntoIterator,
+      I::Item: Modifier<F> {
+    fn modify(self, f: &mut F) {
+        for modifier in self.0 {
+            modifier.modify(f);
+        }
+    }
+}
+
+impl<F: ?Sized, I, E> TryModifier<F> for Sequence<I>
+where I: IntoIterator,
+      I::Item: TryModifier<F, Error = E> {
+    type Error = ChainError<E>;
+
+    fn try_modify(self, f: &mut F) -> Result<(), ChainError<E>> {
+        for (index, modifier) in self.0.into_iter().enumerate() {
+            link(index, modifier.try_modify(f))?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies a clone of every modifier in a slice, in order.
+///
+/// Allows static tables of modifiers to be applied without allocating.
+#[derive(Debug, Clone, Copy)]
+pub struct Cloned<'a, M: 'a>(pub &'a [M]);
+
+impl<'a, F: ?Sized, M> Modifier<F> for Cloned<'a, M>
+where M: Modifier<F> + Clone {
+    fn modify(self, f: &mut F) {
+        for modifier in self.0 {
+            modifier.clone().modify(f);
+        }
+    }
+}
+
+impl<'a, F: ?Sized, M, E> TryModifier<F> for Cloned<'a, M>
+where M: TryModifier<F, Error = E> + Clone {
+    type Error = ChainError<E>;
+
+    fn try_modify(self, f: &mut F) -> Result<(), ChainError<E>> {
+        for (index, modifier) in self.0.iter().enumerate() {
+            link(index, modifier.clone().try_modify(f))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+
+    #[test]
+    fn test_sequence() {
+        let thing = Thing { x: 1 }.set(Sequence((0..3).map(|_| Double)));
+        assert_eq!(thing.x, 8);
+
+        let mut thing = thing;
+        let error = thing.try_set_mut(Sequence((0..3).map(|n| CheckedX(n * 100)))).err();
+        assert_eq!(error, Some(ChainError { index: 2, error: 200 }));
+        assert_eq!(thing.x, 100);
+    }
+
+    #[test]
+    fn test_arrays_and_slices() {
+        static TABLE: [ModifyX; 3] = [ModifyX(1), ModifyX(2), ModifyX(3)];
+
+        let thing = Thing { x: 0 }.set([ModifyX(4), ModifyX(5)]);
+        assert_eq!(thing.x, 5);
+
+        let thing = thing.set(Cloned(&TABLE));
+        assert_eq!(thing.x, 3);
+
+        let mut thing = thing;
+        let error = thing.try_set_mut([CheckedX(7), CheckedX(700)]).err();
+        assert_eq!(error, Some(ChainError { index: 1, error: 700 }));
+        assert_eq!(thing.x, 7);
+    }
+}
@@ -0,0 +1,464 @@
+//! Modifiers for JSON documents and serde types.
+//!
+//! `JsonMergePatch` implements RFC 7396 for any serde type, while the
+//! operations of RFC 6902 (`Add`, `Remove`, `Replace`, `Move`, `Copy` and
+//! `Test`) modify untyped documents and can be combined into a `JsonPatch`.
+
+use std::error::Error;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{self, Map, Value};
+
+use snapshot;
+use {ChainError, Modifier, Set, TryModifier};
+
+impl Set for Value {}
+
+/// A JSON Merge Patch, as described in RFC 7396.
+///
+/// Objects in the patch are merged into the target recursively, `null`
+/// members remove the corresponding member of the target and any other
+/// value replaces the target outright.
+///
+/// The patch can modify any type which can be converted to and from JSON.
+/// As a `TryModifier` it reports patches which produce a value of the wrong
+/// shape, leaving the target unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonMergePatch(pub Value);
+
+impl JsonMergePatch {
+    /// Merge the patch into a JSON document.
+    pub fn apply(self, target: &mut Value) {
+        merge(target, self.0)
+    }
+}
+
+fn merge(target: &mut Value, patch: Value) {
+    let patch = match patch {
+        Value::Object(patch) => patch,
+        patch => {
+            *target = patch;
+            return;
+        }
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+
+    if let Value::Object(ref mut target) = *target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// # Panics
+///
+/// Panics if the patched document cannot be converted back to `T`; use
+/// Set::try_set to handle such patches instead.
+impl<T: Serialize + DeserializeOwned> Modifier<T> for JsonMergePatch {
+    fn modify(self, target: &mut T) {
+        if let Err(error) = self.try_modify(target) {
+            panic!("merge patch does not fit the target: {}", error)
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> TryModifier<T> for JsonMergePatch {
+    type Error = serde_json::Error;
+
+    fn try_modify(self, target: &mut T) -> Result<(), serde_json::Error> {
+        let mut document = serde_json::to_value(&*target)?;
+        self.apply(&mut document);
+        *target = serde_json::from_value(document)?;
+        Ok(())
+    }
+}
+
+/// The error produced when a JSON Patch operation cannot be applied.
+///
+/// Each variant holds the JSON Pointer which caused the error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatchError {
+    /// The pointer is not a valid JSON Pointer.
+    InvalidPointer(String),
+
+    /// There is no value at the pointer.
+    NotFound(String),
+
+    /// A `test` operation found a different value at the pointer.
+    TestFailed(String),
+
+    /// A `move` operation tried to move a value into one of its children.
+    MoveIntoChild(String),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PatchError::InvalidPointer(ref path) => write!(f, "invalid JSON pointer `{}`", path),
+            PatchError::NotFound(ref path) => write!(f, "no value at `{}`", path),
+            PatchError::TestFailed(ref path) => write!(f, "test failed at `{}`", path),
+            PatchError::MoveIntoChild(ref path) => {
+                write!(f, "cannot move `{}` into one of its children", path)
+            }
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+/// Adds a value to an object or inserts it into an array.
+///
+/// A trailing `-` appends to an array, and the empty path replaces the
+/// whole document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Add {
+    /// Where to add the value.
+    pub path: String,
+
+    /// The value to add.
+    pub value: Value,
+}
+
+/// Removes the value at a path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Remove {
+    /// The value to remove.
+    pub path: String,
+}
+
+/// Replaces the value at a path, which must already exist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replace {
+    /// The value to replace.
+    pub path: String,
+
+    /// The replacement value.
+    pub value: Value,
+}
+
+/// Removes the value at one path and adds it at another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Move {
+    /// The value to move.
+    pub from: String,
+
+    /// Where to add the value.
+    pub path: String,
+}
+
+/// Adds a copy of the value at one path at another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Copy {
+    /// The value to copy.
+    pub from: String,
+
+    /// Where to add the copy.
+    pub path: String,
+}
+
+/// Checks that the value at a path is equal to a given value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Test {
+    /// The value to check.
+    pub path: String,
+
+    /// The expected value.
+    pub value: Value,
+}
+
+/// A single JSON Patch operation, serialized as in RFC 6902.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    /// An `add` operation.
+    Add(Add),
+
+    /// A `remove` operation.
+    Remove(Remove),
+
+    /// A `replace` operation.
+    Replace(Replace),
+
+    /// A `move` operation.
+    Move(Move),
+
+    /// A `copy` operation.
+    Copy(Copy),
+
+    /// A `test` operation.
+    Test(Test),
+}
+
+/// A JSON Patch, as described in RFC 6902.
+///
+/// Operations are applied in order. If any of them fails the document is
+/// restored to its original state and the error reports which operation
+/// failed.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonPatch(pub Vec<Operation>);
+
+impl TryModifier<Value> for Add {
+    type Error = PatchError;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), PatchError> {
+        add(document, &self.path, self.value).map_err(|(error, _)| error)
+    }
+}
+
+impl TryModifier<Value> for Remove {
+    type Error = PatchError;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), PatchError> {
+        remove(document, &self.path).map(drop)
+    }
+}
+
+impl TryModifier<Value> for Replace {
+    type Error = PatchError;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), PatchError> {
+        *lookup_mut(document, &self.path)? = self.value;
+        Ok(())
+    }
+}
+
+impl TryModifier<Value> for Move {
+    type Error = PatchError;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), PatchError> {
+        if self.from == self.path {
+            return lookup(document, &self.from).map(drop);
+        }
+        if self.path.starts_with(&self.from) && self.path[self.from.len()..].starts_with('/') {
+            return Err(PatchError::MoveIntoChild(self.from));
+        }
+
+        let value = remove(document, &self.from)?;
+        add(document, &self.path, value).map_err(|(error, value)| {
+            // Put the value back where it was, which cannot fail.
+            let _ = add(document, &self.from, value);
+            error
+        })
+    }
+}
+
+impl TryModifier<Value> for Copy {
+    type Error = PatchError;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), PatchError> {
+        let value = lookup(document, &self.from)?.clone();
+        add(document, &self.path, value).map_err(|(error, _)| error)
+    }
+}
+
+impl TryModifier<Value> for Test {
+    type Error = PatchError;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), PatchError> {
+        if *lookup(document, &self.path)? == self.value {
+            Ok(())
+        } else {
+            Err(PatchError::TestFailed(self.path))
+        }
+    }
+}
+
+impl TryModifier<Value> for Operation {
+    type Error = PatchError;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), PatchError> {
+        match self {
+            Operation::Add(op) => op.try_modify(document),
+            Operation::Remove(op) => op.try_modify(document),
+            Operation::Replace(op) => op.try_modify(document),
+            Operation::Move(op) => op.try_modify(document),
+            Operation::Copy(op) => op.try_modify(document),
+            Operation::Test(op) => op.try_modify(document),
+        }
+    }
+}
+
+impl TryModifier<Value> for JsonPatch {
+    type Error = ChainError<PatchError>;
+
+    fn try_modify(self, document: &mut Value) -> Result<(), ChainError<PatchError>> {
+        snapshot::atomically_cloned(document, |document| self.0.try_modify(document))
+    }
+}
+
+fn lookup<'a>(document: &'a Value, path: &str) -> Result<&'a Value, PatchError> {
+    check(path)?;
+    document.pointer(path).ok_or_else(|| PatchError::NotFound(path.to_owned()))
+}
+
+fn lookup_mut<'a>(document: &'a mut Value, path: &str) -> Result<&'a mut Value, PatchError> {
+    check(path)?;
+    document.pointer_mut(path).ok_or_else(|| PatchError::NotFound(path.to_owned()))
+}
+
+fn check(path: &str) -> Result<(), PatchError> {
+    let mut escapes = path.split('~').skip(1);
+    if (path.is_empty() || path.starts_with('/'))
+        && escapes.all(|rest| rest.starts_with('0') || rest.starts_with('1')) {
+        Ok(())
+    } else {
+        Err(PatchError::InvalidPointer(path.to_owned()))
+    }
+}
+
+/// Split a non-empty pointer into the pointer to its parent and its last token.
+fn split(path: &str) -> Result<(&str, String), PatchError> {
+    check(path)?;
+    match path.rfind('/') {
+        Some(index) => {
+            let token = path[index + 1..].replace("~1", "/").replace("~0", "~");
+            Ok((&path[..index], token))
+        }
+        None => Err(PatchError::InvalidPointer(path.to_owned())),
+    }
+}
+
+/// Parse an array index which must be less than or equal to `len`.
+fn index(token: &str, len: usize) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0'))
+        || !token.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok().filter(|&index| index <= len)
+}
+
+/// Add `value` at `path`, handing it back if that is not possible.
+fn add(document: &mut Value, path: &str, value: Value) -> Result<(), (PatchError, Value)> {
+    if path.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+
+    let (parent, token) = match split(path) {
+        Ok(split) => split,
+        Err(error) => return Err((error, value)),
+    };
+    let not_found = || PatchError::NotFound(path.to_owned());
+    match document.pointer_mut(parent) {
+        Some(&mut Value::Object(ref mut object)) => {
+            object.insert(token, value);
+            Ok(())
+        }
+        Some(&mut Value::Array(ref mut array)) => {
+            let len = array.len();
+            match if token == "-" { Some(len) } else { index(&token, len) } {
+                Some(index) => {
+                    array.insert(index, value);
+                    Ok(())
+                }
+                None => Err((not_found(), value)),
+            }
+        }
+        _ => Err((not_found(), value)),
+    }
+}
+
+/// Remove and return the value at `path`.
+fn remove(document: &mut Value, path: &str) -> Result<Value, PatchError> {
+    let (parent, token) = split(path)?;
+    let removed = match document.pointer_mut(parent) {
+        Some(&mut Value::Object(ref mut object)) => object.remove(&token),
+        Some(&mut Value::Array(ref mut array)) => {
+            let len = array.len();
+            index(&token, len).filter(|&index| index < len).map(|index| array.remove(index))
+        }
+        _ => None,
+    };
+    removed.ok_or_else(|| PatchError::NotFound(path.to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+    use json::*;
+    use json::Replace;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        name: String,
+        tags: Option<Vec<String>>,
+    }
+
+    impl Set for Settings {}
+
+    #[test]
+    fn test_merge_documents() {
+        let document: Value = json!({ "a": "b", "c": { "d": "e", "f": "g" } });
+        let patch = JsonMergePatch(json!({ "a": "z", "c": { "f": null }, "h": [1] }));
+        let document = document.set(patch);
+        assert_eq!(document, json!({ "a": "z", "c": { "d": "e" }, "h": [1] }));
+
+        let document = document.set(JsonMergePatch(json!(["replaced"])));
+        assert_eq!(document, json!(["replaced"]));
+    }
+
+    #[test]
+    fn test_merge_typed_values() {
+        let settings = Settings { name: "a".to_owned(), tags: Some(vec!["x".to_owned()]) };
+        let settings = settings.set(JsonMergePatch(json!({ "name": "b", "tags": null })));
+        assert_eq!(settings, Settings { name: "b".to_owned(), tags: None });
+
+        let mut settings = settings;
+        assert!(settings.try_set_mut(JsonMergePatch(json!({ "name": 5 }))).is_err());
+        assert_eq!(settings.name, "b");
+    }
+
+    #[test]
+    fn test_json_patch_operations() {
+        let mut document = json!({ "a": { "b": [1, 2] }, "c": "d" });
+        document
+            .try_set_mut(Add { path: "/a/b/1".to_owned(), value: json!(5) }).unwrap()
+            .try_set_mut(Add { path: "/a/b/-".to_owned(), value: json!(6) }).unwrap()
+            .try_set_mut(Remove { path: "/c".to_owned() }).unwrap()
+            .try_set_mut(Copy { from: "/a/b".to_owned(), path: "/e".to_owned() }).unwrap()
+            .try_set_mut(Move { from: "/a/b/0".to_owned(), path: "/f~1g".to_owned() }).unwrap()
+            .try_set_mut(Replace { path: "/a".to_owned(), value: json!(null) }).unwrap()
+            .try_set_mut(Test { path: "/f~1g".to_owned(), value: json!(1) }).unwrap();
+        assert_eq!(document, json!({ "a": null, "e": [1, 5, 2, 6], "f/g": 1 }));
+
+        let error = document.try_set_mut(Remove { path: "/e/4".to_owned() }).err();
+        assert_eq!(error, Some(PatchError::NotFound("/e/4".to_owned())));
+
+        let error = document.try_set_mut(Move { from: "/e".to_owned(), path: "/e/0".to_owned() }).err();
+        assert_eq!(error, Some(PatchError::MoveIntoChild("/e".to_owned())));
+
+        let error = document.try_set_mut(Move { from: "/e".to_owned(), path: "/x/y".to_owned() }).err();
+        assert_eq!(error, Some(PatchError::NotFound("/x/y".to_owned())));
+        assert_eq!(document["e"], json!([1, 5, 2, 6]));
+
+        let error = document.try_set_mut(Test { path: "e".to_owned(), value: json!(1) }).err();
+        assert_eq!(error, Some(PatchError::InvalidPointer("e".to_owned())));
+    }
+
+    #[test]
+    fn test_json_patch_is_atomic() {
+        let patch: JsonPatch = ::serde_json::from_value(json!([
+            { "op": "replace", "path": "/a", "value": 2 },
+            { "op": "test", "path": "/a", "value": 3 },
+        ])).unwrap();
+        assert_eq!(patch.0[1], Operation::Test(Test { path: "/a".to_owned(), value: json!(3) }));
+
+        let mut document = json!({ "a": 1 });
+        let error = document.try_set_mut(patch).err();
+        assert_eq!(error, Some(ChainError { index: 1, error: PatchError::TestFailed("/a".to_owned()) }));
+        assert_eq!(document, json!({ "a": 1 }));
+    }
+}
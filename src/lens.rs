@@ -0,0 +1,180 @@
+//! Lenses for focusing modifiers on part of a value.
+
+use std::fmt;
+
+use {Modifier, ModifierOut, ReversibleModifier, TryModifier};
+
+/// A view of some part of `S`, usually one of its fields.
+///
+/// Lenses can be composed to reach deeply nested values, and turn a
+/// modifier of the part into a modifier of the whole with `modify_with`.
+pub trait Lens<S: ?Sized> {
+    /// The part of `S` viewed by the lens.
+    type Target: ?Sized;
+
+    /// View the part of `source`.
+    fn view<'a>(&self, source: &'a S) -> &'a Self::Target where Self: 'a;
+
+    /// Mutably view the part of `source`.
+    fn view_mut<'a>(&self, source: &'a mut S) -> &'a mut Self::Target where Self: 'a;
+
+    /// View part of the target of self through `next`.
+    fn compose<L: Lens<Self::Target>>(self, next: L) -> Compose<Self, L> where Self: Sized {
+        Compose { outer: self, inner: next }
+    }
+
+    /// Apply `modifier` to the part of `S` viewed by self.
+    ///
+    /// The result is a `Modifier`, `TryModifier`, `ReversibleModifier` or
+    /// `ModifierOut` of `S` whenever `modifier` is one of the target.
+    fn modify_with<M>(self, modifier: M) -> Focus<Self, M> where Self: Sized {
+        Focus { lens: self, modifier }
+    }
+}
+
+/// A lens built from a pair of accessor functions.
+///
+/// This is the lens generated for every field by `#[derive(Lens)]`.
+pub struct FieldLens<S: ?Sized, A: ?Sized> {
+    get: fn(&S) -> &A,
+    get_mut: fn(&mut S) -> &mut A,
+}
+
+impl<S: ?Sized, A: ?Sized> FieldLens<S, A> {
+    /// Create a lens from its accessors.
+    pub const fn new(get: fn(&S) -> &A, get_mut: fn(&mut S) -> &mut A) -> FieldLens<S, A> {
+        FieldLens { get, get_mut }
+    }
+}
+
+impl<S: ?Sized, A: ?Sized> Lens<S> for FieldLens<S, A> {
+    type Target = A;
+
+    fn view<'a>(&self, source: &'a S) -> &'a A where Self: 'a {
+        (self.get)(source)
+    }
+
+    fn view_mut<'a>(&self, source: &'a mut S) -> &'a mut A where Self: 'a {
+        (self.get_mut)(source)
+    }
+}
+
+impl<S: ?Sized, A: ?Sized> Clone for FieldLens<S, A> {
+    fn clone(&self) -> FieldLens<S, A> {
+        *self
+    }
+}
+
+impl<S: ?Sized, A: ?Sized> Copy for FieldLens<S, A> {}
+
+impl<S: ?Sized, A: ?Sized> fmt::Debug for FieldLens<S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("FieldLens { .. }")
+    }
+}
+
+/// Two lenses applied one after the other, created by Lens::compose.
+#[derive(Debug, Clone, Copy)]
+pub struct Compose<A, B> {
+    outer: A,
+    inner: B,
+}
+
+impl<S: ?Sized, A, B> Lens<S> for Compose<A, B>
+where A: Lens<S>,
+      B: Lens<A::Target> {
+    type Target = B::Target;
+
+    fn view<'a>(&self, source: &'a S) -> &'a B::Target where Self: 'a {
+        self.inner.view(self.outer.view(source))
+    }
+
+    fn view_mut<'a>(&self, source: &'a mut S) -> &'a mut B::Target where Self: 'a {
+        self.inner.view_mut(self.outer.view_mut(source))
+    }
+}
+
+/// A modifier applied through a lens, created by Lens::modify_with.
+#[derive(Debug, Clone, Copy)]
+pub struct Focus<L, M> {
+    lens: L,
+    modifier: M,
+}
+
+impl<S: ?Sized, L, M> Modifier<S> for Focus<L, M>
+where L: Lens<S>,
+      M: Modifier<L::Target> {
+    fn modify(self, source: &mut S) {
+        self.modifier.modify(self.lens.view_mut(source));
+    }
+}
+
+impl<S: ?Sized, L, M> TryModifier<S> for Focus<L, M>
+where L: Lens<S>,
+      M: TryModifier<L::Target> {
+    type Error = M::Error;
+
+    fn try_modify(self, source: &mut S) -> Result<(), M::Error> {
+        self.modifier.try_modify(self.lens.view_mut(source))
+    }
+}
+
+impl<S: ?Sized, L, M> ReversibleModifier<S> for Focus<L, M>
+where L: Lens<S> + Clone,
+      M: ReversibleModifier<L::Target> {
+    type Inverse = Focus<L, M::Inverse>;
+
+    fn modify_reversible(self, source: &mut S) -> Focus<L, M::Inverse> {
+        let inverse = self.modifier.modify_reversible(self.lens.view_mut(source));
+        Focus { lens: self.lens, modifier: inverse }
+    }
+}
+
+impl<S: ?Sized, L, M> ModifierOut<S> for Focus<L, M>
+where L: Lens<S>,
+      M: ModifierOut<L::Target> {
+    type Output = M::Output;
+
+    fn modify_out(self, source: &mut S) -> M::Output {
+        self.modifier.modify_out(self.lens.view_mut(source))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+
+    struct Outer {
+        thing: Thing,
+        bigger: BiggerThing,
+    }
+
+    impl Set for Outer {}
+
+    const THING: FieldLens<Outer, Thing> = FieldLens::new(|o| &o.thing, |o| &mut o.thing);
+    const BIGGER: FieldLens<Outer, BiggerThing> = FieldLens::new(|o| &o.bigger, |o| &mut o.bigger);
+    const X: FieldLens<Thing, usize> = FieldLens::new(|t| &t.x, |t| &mut t.x);
+
+    #[test]
+    fn test_focus() {
+        let outer = Outer { thing: Thing { x: 1 }, bigger: BiggerThing { first: 2, second: 3 } };
+        let outer = outer.set((
+            THING.modify_with(ModifyX(5)),
+            BIGGER.modify_with(ModifyFirst(6)),
+            THING.modify_with(Double),
+        ));
+        assert_eq!(outer.thing.x, 10);
+        assert_eq!(outer.bigger.first, 6);
+        assert_eq!(*THING.compose(X).view(&outer), 10);
+    }
+
+    #[test]
+    fn test_reversible_focus() {
+        let mut outer = Outer { thing: Thing { x: 1 }, bigger: BiggerThing { first: 2, second: 3 } };
+        let undo = outer.set_reversible(THING.modify_with(ModifyX(5)));
+        assert_eq!(outer.thing.x, 5);
+
+        outer.set_mut(undo);
+        assert_eq!(outer.thing.x, 1);
+    }
+}
@@ -3,56 +3,274 @@
 //! Overloadable modification through both owned and mutable references
 //! to a type with minimal code duplication.
 
+#[cfg(feature = "derive")]
+extern crate modifier_derive;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(any(test, feature = "json"))]
+#[cfg_attr(all(test, feature = "json"), macro_use)]
+extern crate serde_json;
+
+#[cfg(feature = "derive")]
+pub use modifier_derive::{Diff, Lens, Patch, Set};
+
 /// Allows use of the implemented type as an argument to Set::set.
 ///
 /// This allows types to be used for ad-hoc overloading of Set::set
 /// to perform complex updates to the parameter of Modifier.
 pub trait Modifier<F: ?Sized> {
     /// Modify `F` with self.
-    fn modify(self, &mut F);
+    fn modify(self, f: &mut F);
+}
+
+/// A fallible counterpart to `Modifier`, used with Set::try_set.
+///
+/// A single modifier should leave `F` unchanged when it fails. Chains of
+/// modifiers, such as tuples and `Vec`, stop at the first failure and
+/// leave the changes made by earlier elements in place; use
+/// Set::try_set_atomic or Set::try_set_atomic_cloned to undo those too.
+pub trait TryModifier<F: ?Sized> {
+    /// The error produced when the modification fails.
+    type Error;
+
+    /// Try to modify `F` with self.
+    fn try_modify(self, f: &mut F) -> Result<(), Self::Error>;
+}
+
+/// A modifier which can undo itself, used with Set::set_reversible.
+///
+/// Applying a reversible modifier produces its inverse, typically a
+/// modifier holding the previous value of whatever was changed.
+pub trait ReversibleModifier<F: ?Sized>: Modifier<F> {
+    /// The modifier which undoes self.
+    type Inverse: ReversibleModifier<F>;
+
+    /// Modify `F` with self, returning the modifier which undoes the change.
+    fn modify_reversible(self, f: &mut F) -> Self::Inverse;
+}
+
+/// A modifier which can be applied many times through a mutable reference.
+///
+/// This is to `Modifier` what `FnMut` is to `FnOnce`: `&mut M` is a
+/// `Modifier` for every `M: ModifierMut`, so one value can be passed to
+/// Set::set_mut repeatedly, and may update its own state as it goes.
+///
+/// Unlike the closure traits, a `ModifierRef` is not automatically a
+/// `ModifierMut`; borrow it as `&M` to use it where one is expected.
+pub trait ModifierMut<F: ?Sized> {
+    /// Modify `F` with self, keeping self for later use.
+    fn modify_mut(&mut self, f: &mut F);
+}
+
+/// A modifier which can be applied many times through a shared reference.
+///
+/// This is to `Modifier` what `Fn` is to `FnOnce`: `&M` is a `Modifier`
+/// and a `ModifierMut` for every `M: ModifierRef`, and can be used with
+/// Set::set_ref and Set::set_mut_ref.
+pub trait ModifierRef<F: ?Sized> {
+    /// Modify `F` with a reference to self.
+    fn modify_ref(&self, f: &mut F);
+}
+
+/// A modifier which produces a value, used with Set::set_mut_returning.
+///
+/// Typically the output is whatever the modifier replaced, so the previous
+/// value of a field can be recovered without a separate read.
+pub trait ModifierOut<F: ?Sized> {
+    /// The value produced by the modification.
+    type Output;
+
+    /// Modify `F` with self, returning the output.
+    fn modify_out(self, f: &mut F) -> Self::Output;
+}
+
+/// A change which consumes a value and produces the new one, used with
+/// Set::set_owned.
+///
+/// This suits types which are rebuilt rather than mutated, like consuming
+/// builders. `AsTransform` and `AsModifier` convert between transforms and
+/// modifiers.
+pub trait Transform<F> {
+    /// Transform `F` with self.
+    fn transform(self, f: F) -> F;
+}
+
+/// The methods of `Set`, shared with `blanket::Set`.
+macro_rules! set_methods {
+    () => {
+        /// Modify self using the provided modifier.
+        #[inline(always)]
+        fn set<M: $crate::Modifier<Self>>(mut self, modifier: M) -> Self where Self: Sized {
+            modifier.modify(&mut self);
+            self
+        }
+
+        /// Modify self through a mutable reference with the provided modifier.
+        #[inline(always)]
+        fn set_mut<M: $crate::Modifier<Self>>(&mut self, modifier: M) -> &mut Self {
+            modifier.modify(self);
+            self
+        }
+
+        /// Modify self using a modifier which is only borrowed.
+        #[inline(always)]
+        fn set_ref<M: $crate::ModifierRef<Self> + ?Sized>(mut self, modifier: &M) -> Self where Self: Sized {
+            modifier.modify_ref(&mut self);
+            self
+        }
+
+        /// Modify self through a mutable reference with a modifier which is only borrowed.
+        #[inline(always)]
+        fn set_mut_ref<M: $crate::ModifierRef<Self> + ?Sized>(&mut self, modifier: &M) -> &mut Self {
+            modifier.modify_ref(self);
+            self
+        }
+
+        /// Transform self using the provided transform.
+        #[inline(always)]
+        fn set_owned<T: $crate::Transform<Self>>(self, transform: T) -> Self where Self: Sized {
+            transform.transform(self)
+        }
+
+        /// Modify self through a mutable reference with the provided modifier,
+        /// returning its output.
+        #[inline(always)]
+        fn set_mut_returning<M: $crate::ModifierOut<Self>>(&mut self, modifier: M) -> M::Output {
+            modifier.modify_out(self)
+        }
+
+        /// Try to modify self using the provided fallible modifier.
+        #[inline(always)]
+        fn try_set<M: $crate::TryModifier<Self>>(mut self, modifier: M) -> Result<Self, M::Error> where Self: Sized {
+            modifier.try_modify(&mut self)?;
+            Ok(self)
+        }
+
+        /// Try to modify self through a mutable reference with the provided fallible modifier.
+        #[inline(always)]
+        fn try_set_mut<M: $crate::TryModifier<Self>>(&mut self, modifier: M) -> Result<&mut Self, M::Error> {
+            modifier.try_modify(self)?;
+            Ok(self)
+        }
+
+        /// Modify self through a mutable reference with the provided modifier,
+        /// returning a modifier which undoes the change.
+        #[inline(always)]
+        fn set_reversible<M: $crate::ReversibleModifier<Self>>(&mut self, modifier: M) -> M::Inverse {
+            modifier.modify_reversible(self)
+        }
+
+        /// Modify self through a mutable reference, restoring its original
+        /// state if the modifier panics.
+        #[inline(always)]
+        fn set_atomic<M: $crate::Modifier<Self>>(&mut self, modifier: M) -> &mut Self where Self: $crate::Snapshot {
+            let _ = $crate::snapshot::atomically(self, |this| {
+                modifier.modify(this);
+                Ok::<(), ::std::convert::Infallible>(())
+            });
+            self
+        }
+
+        /// Try to modify self through a mutable reference, restoring its
+        /// original state if the modifier fails or panics.
+        #[inline(always)]
+        fn try_set_atomic<M: $crate::TryModifier<Self>>(&mut self, modifier: M) -> Result<&mut Self, M::Error>
+        where Self: $crate::Snapshot {
+            $crate::snapshot::atomically(self, |this| modifier.try_modify(this))?;
+            Ok(self)
+        }
+
+        /// Modify self through a mutable reference, restoring a clone of its
+        /// original state if the modifier panics.
+        #[inline(always)]
+        fn set_atomic_cloned<M: $crate::Modifier<Self>>(&mut self, modifier: M) -> &mut Self where Self: Clone {
+            let _ = $crate::snapshot::atomically_cloned(self, |this| {
+                modifier.modify(this);
+                Ok::<(), ::std::convert::Infallible>(())
+            });
+            self
+        }
+
+        /// Try to modify self through a mutable reference, restoring a clone
+        /// of its original state if the modifier fails or panics.
+        #[inline(always)]
+        fn try_set_atomic_cloned<M: $crate::TryModifier<Self>>(&mut self, modifier: M) -> Result<&mut Self, M::Error>
+        where Self: Clone {
+            $crate::snapshot::atomically_cloned(self, |this| modifier.try_modify(this))?;
+            Ok(self)
+        }
+    };
 }
 
 /// A trait providing the set and set_mut methods for all types.
 ///
 /// Simply implement this for your types and they can be used
-/// with modifiers.
+/// with modifiers. It is implemented for the common std types, and
+/// `blanket::Set` provides the same methods for every type.
 pub trait Set {
-    /// Modify self using the provided modifier.
-    #[inline(always)]
-    fn set<M: Modifier<Self>>(mut self, modifier: M) -> Self where Self: Sized {
-        modifier.modify(&mut self);
-        self
-    }
-
-    /// Modify self through a mutable reference with the provided modifier.
-    #[inline(always)]
-    fn set_mut<M: Modifier<Self>>(&mut self, modifier: M) -> &mut Self {
-        modifier.modify(self);
-        self
-    }
+    set_methods!();
 }
 
+pub use boxed::{BoxModifier, SendBoxModifier};
+pub use closure::{modify_with, try_modify_with, ModifyWith};
+pub use collections::{At, AtKey, ForEach, KeyError, Upsert, Where};
+pub use error::ChainError;
+pub use ext::{ModifierExt, TryModifierExt};
+pub use history::{History, Record};
+pub use iter::{Cloned, Sequence};
+pub use lens::{Compose, FieldLens, Focus, Lens};
+pub use patch::{Diff, Merge, Patchable};
+pub use returning::{Replace, ResetToDefault, Take};
+pub use snapshot::Snapshot;
+pub use transform::{AsModifier, AsTransform};
+
+pub mod blanket;
+pub mod ext;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod num;
+pub mod stdlib;
+
+mod boxed;
+mod closure;
+mod collections;
+mod error;
+mod history;
 mod impls;
+mod iter;
+mod lens;
+mod patch;
+mod returning;
+mod snapshot;
+mod transform;
 
 #[cfg(test)]
 mod test {
     pub use super::*;
 
+    #[derive(Clone)]
     pub struct Thing {
-        x: usize
+        pub x: usize
     }
 
     pub struct BiggerThing {
-        first: usize,
-        second: usize
+        pub first: usize,
+        pub second: usize
     }
 
     impl Set for Thing {}
     impl Set for BiggerThing {}
 
-    pub struct ModifyX(usize);
-    pub struct ModifyFirst(usize);
-    pub struct ModifySecond(usize);
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+    pub struct ModifyX(pub usize);
+    #[derive(Clone)]
+    pub struct CheckedX(pub usize);
+    #[derive(Clone)]
+    pub struct Double;
+    pub struct Explode;
+    pub struct ModifyFirst(pub usize);
+    pub struct ModifySecond(pub usize);
 
     impl Modifier<Thing> for ModifyX {
         fn modify(self, thing: &mut Thing) {
@@ -60,6 +278,36 @@ mod test {
         }
     }
 
+    impl TryModifier<Thing> for CheckedX {
+        type Error = usize;
+
+        fn try_modify(self, thing: &mut Thing) -> Result<(), usize> {
+            if self.0 > 100 { return Err(self.0) }
+            thing.x = self.0;
+            Ok(())
+        }
+    }
+
+    impl ReversibleModifier<Thing> for ModifyX {
+        type Inverse = ModifyX;
+
+        fn modify_reversible(self, thing: &mut Thing) -> ModifyX {
+            ModifyX(::std::mem::replace(&mut thing.x, self.0))
+        }
+    }
+
+    impl Modifier<Thing> for Double {
+        fn modify(self, thing: &mut Thing) {
+            thing.x *= 2;
+        }
+    }
+
+    impl Modifier<Thing> for Explode {
+        fn modify(self, _: &mut Thing) {
+            panic!("explode")
+        }
+    }
+
     impl Modifier<BiggerThing> for ModifyFirst {
         fn modify(self, bigger_thing: &mut BiggerThing) {
             bigger_thing.first = self.0;
@@ -92,6 +340,17 @@ mod test {
         assert_eq!(thing.x, 5);
     }
 
+    #[test]
+    fn test_result_modifier() {
+        let parse = |s: &str| s.parse().map(ModifyX);
+        let thing = Thing { x: 8 }.try_set(parse("5")).ok().unwrap();
+        assert_eq!(thing.x, 5);
+
+        let mut thing = thing;
+        assert!(thing.try_set_mut(parse("five")).is_err());
+        assert_eq!(thing.x, 5);
+    }
+
     #[test]
     fn test_tuple_chains() {
         let thing = Thing { x: 8 }.set((ModifyX(5), ModifyX(112)));
@@ -104,5 +363,131 @@ mod test {
         assert_eq!(bigger_thing.first, 10);
         assert_eq!(bigger_thing.second, 12);
     }
-}
 
+    #[test]
+    fn test_try_set_and_try_set_mut() {
+        let mut thing = Thing { x: 6 };
+        assert!(thing.try_set_mut(CheckedX(8)).is_ok());
+        assert_eq!(thing.x, 8);
+        assert_eq!(thing.try_set_mut(CheckedX(101)).err(), Some(101));
+        assert_eq!(thing.x, 8);
+
+        let thing = thing.try_set(CheckedX(9)).ok().unwrap();
+        assert_eq!(thing.x, 9);
+    }
+
+    #[test]
+    fn test_try_tuple_chains() {
+        let mut thing = Thing { x: 8 };
+        let error = thing.try_set_mut((CheckedX(5), CheckedX(500), CheckedX(7))).err();
+        assert_eq!(error, Some(ChainError { index: 1, error: 500 }));
+        assert_eq!(thing.x, 5);
+
+        let error = thing.try_set_mut((None::<CheckedX>, Some(CheckedX(9)))).err();
+        assert_eq!(error, None);
+        assert_eq!(thing.x, 9);
+    }
+
+    #[test]
+    fn test_set_reversible() {
+        let mut thing = Thing { x: 1 };
+        let undo = thing.set_reversible((ModifyX(5), None::<ModifyX>, Some(ModifyX(6))));
+        assert_eq!(thing.x, 6);
+
+        let redo = thing.set_reversible(undo);
+        assert_eq!(thing.x, 1);
+
+        thing.set_reversible(redo);
+        assert_eq!(thing.x, 6);
+
+        let undo = thing.set_reversible(vec![ModifyX(7), ModifyX(8)]);
+        thing.set_mut(undo);
+        assert_eq!(thing.x, 6);
+    }
+
+    #[test]
+    fn test_atomic_chains() {
+        let mut thing = Thing { x: 8 };
+        let error = thing.try_set_atomic_cloned((CheckedX(5), CheckedX(500))).err();
+        assert_eq!(error, Some(ChainError { index: 1, error: 500 }));
+        assert_eq!(thing.x, 8);
+
+        let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+            thing.set_atomic_cloned((ModifyX(5), Explode));
+        }));
+        assert!(result.is_err());
+        assert_eq!(thing.x, 8);
+
+        thing.set_atomic_cloned((ModifyX(5), ModifyX(6)));
+        assert_eq!(thing.x, 6);
+    }
+
+    #[test]
+    fn test_reusable_modifiers() {
+        struct AddX(usize);
+        struct Countdown(usize);
+
+        impl ModifierRef<Thing> for AddX {
+            fn modify_ref(&self, thing: &mut Thing) {
+                thing.x += self.0;
+            }
+        }
+
+        impl ModifierMut<Thing> for Countdown {
+            fn modify_mut(&mut self, thing: &mut Thing) {
+                thing.x += self.0;
+                self.0 = self.0.saturating_sub(1);
+            }
+        }
+
+        let add = AddX(2);
+        let thing = Thing { x: 0 }.set_ref(&add).set_ref(&(AddX(1), Some(AddX(1))));
+        assert_eq!(thing.x, 4);
+
+        let mut countdown = (Countdown(3), vec![&add]);
+        let mut thing = thing.set(&mut countdown);
+        thing.set_mut(&mut countdown).set_mut_ref(&add);
+        assert_eq!(thing.x, 15);
+        assert_eq!((countdown.0).0, 1);
+    }
+
+    #[test]
+    fn test_std_types() {
+        let name = String::from("mod").set(modify_with(|name: &mut String| name.push_str("ifier")));
+        assert_eq!(name, "modifier");
+
+        let mut counts = vec![1, 2];
+        counts.set_mut(At(0, Some(modify_with(|count: &mut i32| *count -= 1))));
+        assert_eq!(counts, [0, 2]);
+
+        let mut boxed = Box::new(Thing { x: 1 });
+        boxed.set_mut(ModifyX(2));
+        assert_eq!(boxed.x, 2);
+    }
+
+    #[test]
+    fn test_set_mut_returning() {
+        let mut thing = Thing { x: 1 };
+        assert_eq!(thing.set_mut_returning(Replace(Thing { x: 2 })).x, 1);
+
+        let mut pair = (String::from("a"), vec![1, 2]);
+        let lens = FieldLens::new(|pair: &(String, Vec<i32>)| &pair.0, |pair| &mut pair.0);
+        let old = pair.0.set_mut_returning((Replace(String::from("b")), Take, Replace(String::from("c"))));
+        assert_eq!(old, (String::from("a"), String::from("b"), String::new()));
+        assert_eq!(lens.modify_with(Take).modify_out(&mut pair), "c");
+        assert_eq!(pair.1.set_mut_returning(Some(ResetToDefault)), Some(vec![1, 2]));
+        assert!(pair.0.is_empty() && pair.1.is_empty());
+    }
+
+    #[test]
+    fn test_long_tuple_chains() {
+        let thing = Thing { x: 1 }.set((
+            Double, Double, Double, Double, Double, Double, Double, Double,
+            Double, Double, Double, Double, Double, Double, Double, Double,
+        ));
+        assert_eq!(thing.x, 1 << 16);
+
+        let thing = thing.set(());
+        assert_eq!(thing.x, 1 << 16);
+    }
+}
@@ -0,0 +1,150 @@
+//! Arithmetic modifiers for numbers.
+//!
+//! `Add`, `Sub` and `Mul` work with any type implementing the matching
+//! assignment operator. For the primitive integers they can also be
+//! wrapped in `Saturating`, or in `Checked` to fail on overflow:
+//!
+//! ```
+//! # use modifier::Set;
+//! # use modifier::num::{Add, Checked, Overflow, Saturating};
+//! let count = 250u8.set(Saturating(Add(10)));
+//! assert_eq!(count, 255);
+//!
+//! let error = 250u8.try_set(Checked(Add(10)));
+//! assert_eq!(error, Err(Overflow));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{AddAssign, MulAssign, SubAssign};
+
+use {Modifier, TryModifier};
+
+/// Adds a value to a number.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Add<T>(pub T);
+
+/// Subtracts a value from a number.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sub<T>(pub T);
+
+/// Multiplies a number by a value.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mul<T>(pub T);
+
+/// Restricts a number to lie between a minimum and a maximum, inclusive.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clamp<T>(pub T, pub T);
+
+/// Applies an `Add`, `Sub` or `Mul` to a primitive integer, stopping at
+/// the bounds of the integer type instead of overflowing.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Saturating<O>(pub O);
+
+/// Applies an `Add`, `Sub` or `Mul` to a primitive integer as a
+/// `TryModifier`, failing with `Overflow` instead of overflowing.
+///
+/// The integer is left unchanged when the operation fails.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checked<O>(pub O);
+
+/// The error produced when a `Checked` operation overflows.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("arithmetic overflow")
+    }
+}
+
+impl Error for Overflow {}
+
+impl<T: AddAssign<R>, R> Modifier<T> for Add<R> {
+    fn modify(self, number: &mut T) {
+        *number += self.0;
+    }
+}
+
+impl<T: SubAssign<R>, R> Modifier<T> for Sub<R> {
+    fn modify(self, number: &mut T) {
+        *number -= self.0;
+    }
+}
+
+impl<T: MulAssign<R>, R> Modifier<T> for Mul<R> {
+    fn modify(self, number: &mut T) {
+        *number *= self.0;
+    }
+}
+
+impl<T: PartialOrd> Modifier<T> for Clamp<T> {
+    fn modify(self, number: &mut T) {
+        let Clamp(min, max) = self;
+        if *number < min {
+            *number = min;
+        } else if *number > max {
+            *number = max;
+        }
+    }
+}
+
+macro_rules! integer_impls {
+    ($($int:ty)+) => {$(
+        integer_impls!($int, Add, saturating_add, checked_add);
+        integer_impls!($int, Sub, saturating_sub, checked_sub);
+        integer_impls!($int, Mul, saturating_mul, checked_mul);
+    )+};
+    ($int:ty, $op:ident, $saturating:ident, $checked:ident) => {
+        impl Modifier<$int> for Saturating<$op<$int>> {
+            fn modify(self, number: &mut $int) {
+                *number = number.$saturating((self.0).0);
+            }
+        }
+
+        impl TryModifier<$int> for Checked<$op<$int>> {
+            type Error = Overflow;
+
+            fn try_modify(self, number: &mut $int) -> Result<(), Overflow> {
+                *number = number.$checked((self.0).0).ok_or(Overflow)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+integer_impls!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Set;
+
+    #[test]
+    fn test_arithmetic() {
+        let count = 1u32.set((Add(4), Mul(3), Sub(5), Clamp(0, 8)));
+        assert_eq!(count, 8);
+
+        let ratio = 0.5f64.set((Mul(4.0), Clamp(0.0, 1.0)));
+        assert_eq!(ratio, 1.0);
+    }
+
+    #[test]
+    fn test_saturating_and_checked() {
+        assert_eq!(250u8.set(Saturating(Add(10))), 255);
+        assert_eq!(5i32.set(Saturating(Sub(i32::MAX))).set(Saturating(Sub(10))), i32::MIN);
+
+        let mut count = 250u8;
+        assert_eq!(count.try_set_mut((Checked(Add(5)), Checked(Add(1)))).err().map(|e| e.index), Some(1));
+        assert_eq!(count, 255);
+        assert_eq!(count.try_set_mut(Checked(Mul(2))).err(), Some(Overflow));
+        assert_eq!(count, 255);
+    }
+}
@@ -0,0 +1,38 @@
+//! Partial updates, usually generated with `#[derive(Patch)]`.
+
+use Modifier;
+
+/// Types with a partial-update counterpart.
+///
+/// A patch holds an optional new value for every field of the patched
+/// type and, as a modifier, overwrites only the fields which are present.
+pub trait Patchable {
+    /// The patch type of self.
+    type Patch: Modifier<Self> + Merge + Default;
+}
+
+/// Types which can compute the patch between two values.
+///
+/// Fields left out of the patch type are not compared, so they are not
+/// carried over by the patch.
+pub trait Diff: Patchable {
+    /// The smallest patch turning self into `new`, such that applying it
+    /// to self produces a value equal to `new`.
+    fn diff(&self, new: &Self) -> Self::Patch;
+}
+
+/// Values which can be combined, with the later value taking precedence.
+pub trait Merge {
+    /// Combine self with `other`, preferring `other` where both are present.
+    fn merge(self, other: Self) -> Self;
+}
+
+/// Nested patches are merged, otherwise the later value wins.
+impl<T: Merge> Merge for Option<T> {
+    fn merge(self, other: Option<T>) -> Option<T> {
+        match (self, other) {
+            (Some(this), Some(other)) => Some(this.merge(other)),
+            (this, other) => other.or(this),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Modifiers which return the value they replace.
+
+use std::mem;
+
+use {Modifier, ModifierOut, ReversibleModifier};
+
+/// Replaces a value, returning the previous value as its output.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Replace<T>(pub T);
+
+/// Replaces a value with its default, returning the previous value as its output.
+///
+/// Unlike `ResetToDefault`, `Take` is only a `ModifierOut`.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Take;
+
+/// Resets a value to its default.
+///
+/// As a `ModifierOut` the previous value is the output, and as a
+/// `ReversibleModifier` it is restored by the inverse.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResetToDefault;
+
+impl<T> Modifier<T> for Replace<T> {
+    fn modify(self, value: &mut T) {
+        *value = self.0;
+    }
+}
+
+impl<T> ReversibleModifier<T> for Replace<T> {
+    type Inverse = Replace<T>;
+
+    fn modify_reversible(self, value: &mut T) -> Replace<T> {
+        Replace(mem::replace(value, self.0))
+    }
+}
+
+impl<T> ModifierOut<T> for Replace<T> {
+    type Output = T;
+
+    fn modify_out(self, value: &mut T) -> T {
+        mem::replace(value, self.0)
+    }
+}
+
+impl<T: Default> ModifierOut<T> for Take {
+    type Output = T;
+
+    fn modify_out(self, value: &mut T) -> T {
+        mem::take(value)
+    }
+}
+
+impl<T: Default> Modifier<T> for ResetToDefault {
+    fn modify(self, value: &mut T) {
+        *value = T::default();
+    }
+}
+
+impl<T: Default> ReversibleModifier<T> for ResetToDefault {
+    type Inverse = Replace<T>;
+
+    fn modify_reversible(self, value: &mut T) -> Replace<T> {
+        Replace(mem::take(value))
+    }
+}
+
+impl<T: Default> ModifierOut<T> for ResetToDefault {
+    type Output = T;
+
+    fn modify_out(self, value: &mut T) -> T {
+        mem::take(value)
+    }
+}
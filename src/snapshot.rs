@@ -0,0 +1,93 @@
+/// Types which can capture their current state and later restore it.
+///
+/// Used by Set::set_atomic and Set::try_set_atomic to undo a partially
+/// applied modifier, for types which cannot be cloned or can be restored
+/// more cheaply than they can be cloned. `Clone` types can instead use
+/// Set::set_atomic_cloned and Set::try_set_atomic_cloned without
+/// implementing this trait.
+pub trait Snapshot {
+    /// The captured state.
+    type State;
+
+    /// Capture the current state of self.
+    fn snapshot(&self) -> Self::State;
+
+    /// Restore self to a previously captured state.
+    fn restore(&mut self, state: Self::State);
+}
+
+/// Restores the target to its saved state when dropped, unless committed.
+struct Guard<'a, T: ?Sized + 'a, S> {
+    target: &'a mut T,
+    state: Option<S>,
+    restore: fn(&mut T, S),
+}
+
+impl<'a, T: ?Sized, S> Drop for Guard<'a, T, S> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            (self.restore)(self.target, state);
+        }
+    }
+}
+
+fn guarded<T, S, E, F>(target: &mut T, state: S, restore: fn(&mut T, S), modify: F) -> Result<(), E>
+where T: ?Sized,
+      F: FnOnce(&mut T) -> Result<(), E> {
+    let mut guard = Guard { target, state: Some(state), restore };
+    let result = modify(guard.target);
+    if result.is_ok() {
+        guard.state = None;
+    }
+    result
+}
+
+/// Run `modify` on `target`, restoring `target` if it fails or panics.
+pub fn atomically<T, E, F>(target: &mut T, modify: F) -> Result<(), E>
+where T: Snapshot + ?Sized,
+      F: FnOnce(&mut T) -> Result<(), E> {
+    let state = target.snapshot();
+    guarded(target, state, T::restore, modify)
+}
+
+/// Run `modify` on `target`, restoring a clone of `target` if it fails or panics.
+pub fn atomically_cloned<T, E, F>(target: &mut T, modify: F) -> Result<(), E>
+where T: Clone,
+      F: FnOnce(&mut T) -> Result<(), E> {
+    let state = target.clone();
+    guarded(target, state, |target, state| *target = state, modify)
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+
+    /// Only ever grows, so the length is enough to restore it.
+    struct Log(Vec<usize>);
+
+    impl Set for Log {}
+
+    impl Snapshot for Log {
+        type State = usize;
+
+        fn snapshot(&self) -> usize {
+            self.0.len()
+        }
+
+        fn restore(&mut self, len: usize) {
+            self.0.truncate(len);
+        }
+    }
+
+    #[test]
+    fn test_custom_snapshot() {
+        let push = |value| modify_with(move |log: &mut Log| log.0.push(value));
+        let fail = try_modify_with(|_: &mut Log| Err("full"));
+
+        let mut log = Log(vec![1]);
+        log.set_atomic(push(2));
+        let error = log.try_set_atomic((Ok(push(3)), fail)).err().map(|error| error.error);
+        assert_eq!(error, Some("full"));
+        assert_eq!(log.0, [1, 2]);
+    }
+}
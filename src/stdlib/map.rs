@@ -0,0 +1,67 @@
+//! Modifiers for `HashMap` and `BTreeMap`.
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+use Modifier;
+
+/// Inserts a value at a key of a map, replacing any previous value.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Insert<K, V>(pub K, pub V);
+
+impl<K, V, S> Modifier<HashMap<K, V, S>> for Insert<K, V>
+where K: Hash + Eq,
+      S: BuildHasher {
+    fn modify(self, map: &mut HashMap<K, V, S>) {
+        map.insert(self.0, self.1);
+    }
+}
+
+impl<K: Ord, V> Modifier<BTreeMap<K, V>> for Insert<K, V> {
+    fn modify(self, map: &mut BTreeMap<K, V>) {
+        map.insert(self.0, self.1);
+    }
+}
+
+/// Removes a key and its value from a map, if present.
+///
+/// The key is borrowed, as in `HashMap::remove`, so `Remove("a")` works on a
+/// map with `String` keys.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Remove<Q>(pub Q);
+
+impl<K, V, S, Q: ?Sized> Modifier<HashMap<K, V, S>> for Remove<&Q>
+where K: Borrow<Q> + Hash + Eq,
+      Q: Hash + Eq,
+      S: BuildHasher {
+    fn modify(self, map: &mut HashMap<K, V, S>) {
+        map.remove(self.0);
+    }
+}
+
+impl<K, V, Q: ?Sized> Modifier<BTreeMap<K, V>> for Remove<&Q>
+where K: Borrow<Q> + Ord,
+      Q: Ord {
+    fn modify(self, map: &mut BTreeMap<K, V>) {
+        map.remove(self.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use super::*;
+    use Set;
+
+    #[test]
+    fn test_map_modifiers() {
+        let map = HashMap::new().set((Insert("a", 1), Insert("b", 2), Insert("a", 3), Remove("b")));
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [("a", 3)]);
+
+        let map = HashMap::new().set((Insert("a".to_owned(), 1), Insert("b".to_owned(), 2), Remove("a")));
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [("b".to_owned(), 2)]);
+    }
+}
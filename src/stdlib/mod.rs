@@ -0,0 +1,133 @@
+//! Ready-made modifiers for the std collections and `String`.
+//!
+//! Modifiers which apply to many of these types live at the top of this
+//! module, and those specific to one kind of collection live in the
+//! submodules. Together with the `Set` impls for std types, collection
+//! fields can be updated through the same modifiers as any other type:
+//!
+//! ```
+//! # use modifier::Set;
+//! # use modifier::stdlib::string;
+//! let name = String::new().set(string::Append("x"));
+//! assert_eq!(name, "x");
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::iter;
+
+use Modifier;
+
+pub mod map;
+pub mod set;
+pub mod string;
+pub mod vec;
+
+/// Removes every element of a collection or `String`.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Clear;
+
+macro_rules! clear_impls {
+    ($([$($params:tt)*] $ty:ty,)+) => {
+        $(impl<$($params)*> Modifier<$ty> for Clear {
+            fn modify(self, collection: &mut $ty) {
+                collection.clear();
+            }
+        })+
+    };
+}
+
+clear_impls! {
+    [] String,
+    [T] Vec<T>, [T] VecDeque<T>, [T] LinkedList<T>, [T] BinaryHeap<T>,
+    [K, V, S] HashMap<K, V, S>, [K, V] BTreeMap<K, V>,
+    [T, S] HashSet<T, S>, [T] BTreeSet<T>,
+}
+
+/// Adds every item of an iterator to any collection implementing `Extend`.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extend<I>(pub I);
+
+impl<C: ?Sized, I> Modifier<C> for Extend<I>
+where I: IntoIterator,
+      C: iter::Extend<I::Item> {
+    fn modify(self, collection: &mut C) {
+        collection.extend(self.0);
+    }
+}
+
+/// Keeps only the elements of a collection which match a predicate.
+///
+/// The predicate takes the same arguments as the `retain` method of the
+/// collection: `&T` for `Vec`, `VecDeque` and sets, `char` for `String`,
+/// and `&K, &mut V` for maps.
+#[derive(Debug, Clone, Copy)]
+pub struct Retain<P>(pub P);
+
+macro_rules! retain_impls {
+    ($([$($params:tt)*] $ty:ty, $predicate:path;)+) => {
+        $(impl<$($params)*, P: $predicate> Modifier<$ty> for Retain<P> {
+            fn modify(self, collection: &mut $ty) {
+                collection.retain(self.0);
+            }
+        })+
+    };
+}
+
+retain_impls! {
+    [T] Vec<T>, FnMut(&T) -> bool;
+    [T] VecDeque<T>, FnMut(&T) -> bool;
+    [T: Ord] BinaryHeap<T>, FnMut(&T) -> bool;
+    [T: Ord] BTreeSet<T>, FnMut(&T) -> bool;
+    [K: Ord, V] BTreeMap<K, V>, FnMut(&K, &mut V) -> bool;
+}
+
+impl<P: FnMut(char) -> bool> Modifier<String> for Retain<P> {
+    fn modify(self, string: &mut String) {
+        string.retain(self.0);
+    }
+}
+
+impl<K, V, S, P> Modifier<HashMap<K, V, S>> for Retain<P>
+where K: Hash + Eq,
+      S: BuildHasher,
+      P: FnMut(&K, &mut V) -> bool {
+    fn modify(self, map: &mut HashMap<K, V, S>) {
+        map.retain(self.0);
+    }
+}
+
+impl<T, S, P> Modifier<HashSet<T, S>> for Retain<P>
+where T: Hash + Eq,
+      S: BuildHasher,
+      P: FnMut(&T) -> bool {
+    fn modify(self, set: &mut HashSet<T, S>) {
+        set.retain(self.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashSet};
+    use super::*;
+    use Set;
+
+    #[test]
+    fn test_shared_modifiers() {
+        let numbers = vec![1, 2, 3].set((Extend(4..7), Retain(|n: &i32| n % 2 == 0)));
+        assert_eq!(numbers, [2, 4, 6]);
+
+        let mut map = BTreeMap::new();
+        map.set_mut(Extend(vec![("a", 1), ("b", 2)]))
+            .set_mut(Retain(|_: &&str, value: &mut i32| *value > 1));
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [("b", 2)]);
+
+        let set: HashSet<_> = (0..3).collect();
+        assert!(set.set(Clear).is_empty());
+
+        let name = String::from("m-o-d").set(Retain(|c: char| c != '-'));
+        assert_eq!(name, "mod");
+    }
+}
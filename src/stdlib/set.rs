@@ -0,0 +1,67 @@
+//! Modifiers for `HashSet` and `BTreeSet`.
+
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+use Modifier;
+
+/// Adds a value to a set.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Insert<T>(pub T);
+
+impl<T, S> Modifier<HashSet<T, S>> for Insert<T>
+where T: Hash + Eq,
+      S: BuildHasher {
+    fn modify(self, set: &mut HashSet<T, S>) {
+        set.insert(self.0);
+    }
+}
+
+impl<T: Ord> Modifier<BTreeSet<T>> for Insert<T> {
+    fn modify(self, set: &mut BTreeSet<T>) {
+        set.insert(self.0);
+    }
+}
+
+/// Removes a value from a set, if present.
+///
+/// The value is borrowed, as in `HashSet::remove`, so `Remove("a")` works on
+/// a set of `String`s.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Remove<Q>(pub Q);
+
+impl<T, S, Q: ?Sized> Modifier<HashSet<T, S>> for Remove<&Q>
+where T: Borrow<Q> + Hash + Eq,
+      Q: Hash + Eq,
+      S: BuildHasher {
+    fn modify(self, set: &mut HashSet<T, S>) {
+        set.remove(self.0);
+    }
+}
+
+impl<T, Q: ?Sized> Modifier<BTreeSet<T>> for Remove<&Q>
+where T: Borrow<Q> + Ord,
+      Q: Ord {
+    fn modify(self, set: &mut BTreeSet<T>) {
+        set.remove(self.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashSet};
+    use super::*;
+    use Set;
+
+    #[test]
+    fn test_set_modifiers() {
+        let set = BTreeSet::new().set((Insert(3), Insert(1), Insert(2), Remove(&3)));
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), [1, 2]);
+
+        let set = HashSet::new().set((Insert("a".to_owned()), Insert("b".to_owned()), Remove("a")));
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), ["b"]);
+    }
+}
@@ -0,0 +1,57 @@
+//! Modifiers for `String`.
+
+use Modifier;
+
+/// Appends a string slice to the end of a `String`.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Append<S>(pub S);
+
+impl<S: AsRef<str>> Modifier<String> for Append<S> {
+    fn modify(self, string: &mut String) {
+        string.push_str(self.0.as_ref());
+    }
+}
+
+/// Replaces every match of the first string with the second.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Replace<A, B>(pub A, pub B);
+
+impl<A: AsRef<str>, B: AsRef<str>> Modifier<String> for Replace<A, B> {
+    fn modify(self, string: &mut String) {
+        let (from, to) = (self.0.as_ref(), self.1.as_ref());
+        if string.contains(from) {
+            *string = string.replace(from, to);
+        }
+    }
+}
+
+/// Removes leading and trailing whitespace from a `String` in place.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Trim;
+
+impl Modifier<String> for Trim {
+    fn modify(self, string: &mut String) {
+        let end = string.trim_end().len();
+        string.truncate(end);
+        let start = end - string.trim_start().len();
+        string.drain(..start);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Set;
+
+    #[test]
+    fn test_string_modifiers() {
+        let name = String::new().set(Append("x"));
+        assert_eq!(name, "x");
+
+        let name = String::from("  rust modifier \n").set((Trim, Replace("rust ", ""), Append("s")));
+        assert_eq!(name, "modifiers");
+    }
+}
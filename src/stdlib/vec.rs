@@ -0,0 +1,143 @@
+//! Modifiers for `Vec`.
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use {KeyError, Modifier, TryModifier};
+
+/// Appends an element to the back of a `Vec` or `VecDeque`.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Push<T>(pub T);
+
+impl<T> Modifier<Vec<T>> for Push<T> {
+    fn modify(self, vec: &mut Vec<T>) {
+        vec.push(self.0);
+    }
+}
+
+impl<T> Modifier<VecDeque<T>> for Push<T> {
+    fn modify(self, deque: &mut VecDeque<T>) {
+        deque.push_back(self.0);
+    }
+}
+
+/// Inserts an element at an index of a `Vec`, shifting later elements back.
+///
+/// Does nothing if the index is greater than the length; as a
+/// `TryModifier` it reports the index instead.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Insert<T>(pub usize, pub T);
+
+impl<T> Modifier<Vec<T>> for Insert<T> {
+    fn modify(self, vec: &mut Vec<T>) {
+        let _ = self.try_modify(vec);
+    }
+}
+
+impl<T> TryModifier<Vec<T>> for Insert<T> {
+    type Error = KeyError<usize>;
+
+    fn try_modify(self, vec: &mut Vec<T>) -> Result<(), KeyError<usize>> {
+        if self.0 > vec.len() { return Err(KeyError(self.0)) }
+        vec.insert(self.0, self.1);
+        Ok(())
+    }
+}
+
+/// Removes the element at an index of a `Vec`, shifting later elements forward.
+///
+/// Does nothing if the index is out of bounds; as a `TryModifier` it
+/// reports the index instead.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Remove(pub usize);
+
+impl<T> Modifier<Vec<T>> for Remove {
+    fn modify(self, vec: &mut Vec<T>) {
+        let _ = self.try_modify(vec);
+    }
+}
+
+impl<T> TryModifier<Vec<T>> for Remove {
+    type Error = KeyError<usize>;
+
+    fn try_modify(self, vec: &mut Vec<T>) -> Result<(), KeyError<usize>> {
+        if self.0 >= vec.len() { return Err(KeyError(self.0)) }
+        vec.remove(self.0);
+        Ok(())
+    }
+}
+
+/// Shortens a `Vec`, `VecDeque` or `String` to at most the given length.
+///
+/// The length of a `String` is in bytes, and must lie on a char boundary.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Truncate(pub usize);
+
+impl<T> Modifier<Vec<T>> for Truncate {
+    fn modify(self, vec: &mut Vec<T>) {
+        vec.truncate(self.0);
+    }
+}
+
+impl<T> Modifier<VecDeque<T>> for Truncate {
+    fn modify(self, deque: &mut VecDeque<T>) {
+        deque.truncate(self.0);
+    }
+}
+
+impl Modifier<String> for Truncate {
+    fn modify(self, string: &mut String) {
+        string.truncate(self.0);
+    }
+}
+
+/// Sorts a `Vec` or slice with a comparator, keeping equal elements in order.
+#[derive(Debug, Clone, Copy)]
+pub struct SortBy<C>(pub C);
+
+impl<T, C: FnMut(&T, &T) -> Ordering> Modifier<[T]> for SortBy<C> {
+    fn modify(self, slice: &mut [T]) {
+        slice.sort_by(self.0);
+    }
+}
+
+impl<T, C: FnMut(&T, &T) -> Ordering> Modifier<Vec<T>> for SortBy<C> {
+    fn modify(self, vec: &mut Vec<T>) {
+        vec.sort_by(self.0);
+    }
+}
+
+/// Removes consecutive repeated elements of a `Vec`.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dedup;
+
+impl<T: PartialEq> Modifier<Vec<T>> for Dedup {
+    fn modify(self, vec: &mut Vec<T>) {
+        vec.dedup();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Set;
+
+    #[test]
+    fn test_vec_modifiers() {
+        let numbers = vec![3, 1].set((Push(2), Push(3), Insert(0, 1), Remove(2)));
+        assert_eq!(numbers, [1, 3, 2, 3]);
+
+        let numbers = numbers.set((SortBy(|a: &i32, b: &i32| b.cmp(a)), Dedup, Truncate(2)));
+        assert_eq!(numbers, [3, 2]);
+
+        let mut numbers = numbers;
+        assert_eq!(numbers.try_set_mut(Insert(3, 0)).err(), Some(KeyError(3)));
+        assert_eq!(numbers.try_set_mut(Remove(2)).err(), Some(KeyError(2)));
+        assert_eq!(numbers, [3, 2]);
+    }
+}
@@ -0,0 +1,82 @@
+//! Adapters between modifiers and transforms.
+//!
+//! A blanket `Transform<F>` impl for every `Modifier<F>` would overlap with
+//! the impls for tuples and the other std types in this crate, so the
+//! conversion is explicit in both directions.
+
+use std::mem;
+
+use {Modifier, Transform};
+
+/// Uses a modifier as a transform, by applying it to the owned value.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct AsTransform<M>(pub M);
+
+impl<F, M: Modifier<F>> Transform<F> for AsTransform<M> {
+    fn transform(self, mut f: F) -> F {
+        self.0.modify(&mut f);
+        f
+    }
+}
+
+/// Uses a transform as a modifier of a type implementing `Default`.
+///
+/// The value is taken out of the target, leaving the default behind while
+/// the transform runs, so the target holds the default if it panics.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct AsModifier<T>(pub T);
+
+impl<F: Default, T: Transform<F>> Modifier<F> for AsModifier<T> {
+    fn modify(self, f: &mut F) {
+        *f = self.0.transform(mem::take(f));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test::*;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Request {
+        path: String,
+        headers: Vec<String>,
+    }
+
+    impl Set for Request {}
+
+    struct Header(&'static str);
+    struct Path(&'static str);
+
+    impl Transform<Request> for Header {
+        fn transform(self, request: Request) -> Request {
+            let mut headers = request.headers;
+            headers.push(self.0.to_owned());
+            Request { headers, ..request }
+        }
+    }
+
+    impl Transform<Request> for Path {
+        fn transform(self, request: Request) -> Request {
+            Request { path: self.0.to_owned(), ..request }
+        }
+    }
+
+    #[test]
+    fn test_set_owned() {
+        let request = Request::default().set_owned((Path("/"), Some(Header("a")), vec![Header("b")]));
+        assert_eq!(request.path, "/");
+        assert_eq!(request.headers, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_bridges() {
+        let thing = Thing { x: 1 }.set_owned((AsTransform(ModifyX(3)), AsTransform(Double)));
+        assert_eq!(thing.x, 6);
+
+        let mut request = Request::default();
+        request.set_mut((AsModifier(Path("/a")), AsModifier(Header("b"))));
+        assert_eq!(request, Request { path: "/a".to_owned(), headers: vec!["b".to_owned()] });
+    }
+}